@@ -0,0 +1,141 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Parse, part 1 and part 2 timed separately so a slow parser can't hide in a part
+pub type Bench = fn(&str, usize) -> Result<[Timing; 3]>;
+
+// Durations are saved as whole nanoseconds so two runs' files diff line by line
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub runs: usize,
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub max: Duration,
+}
+
+mod nanos {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u64(d.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_nanos(u64::deserialize(d)?))
+    }
+}
+
+impl Timing {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Timing> {
+        samples.sort();
+        Some(Timing {
+            runs: samples.len(),
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:>10.3?}  median {:>10.3?}  max {:>10.3?}",
+            self.min, self.median, self.max
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DayReport {
+    pub day: u8,
+    pub parse: Timing,
+    pub part1: Timing,
+    pub part2: Timing,
+}
+
+// Calls f `runs` times and summarises how long each call took
+pub fn time<T>(runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<Timing> {
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f()?);
+        samples.push(start.elapsed());
+    }
+    Timing::from_samples(samples)
+        .ok_or_else(|| Error::InvalidInput("a benchmark needs at least one run".to_string()))
+}
+
+// The parts only ever see an already parsed input, so parsing is paid once for them
+pub fn bench_solution<S: Solution>(input: &str, runs: usize) -> Result<[Timing; 3]> {
+    let parse = time(runs, || S::parse(input))?;
+    let parsed = S::parse(input)?;
+    let part1 = time(runs, || S::part1(&parsed))?;
+    let part2 = time(runs, || S::part2(&parsed))?;
+    Ok([parse, part1, part2])
+}
+
+pub fn save(path: &Path, reports: &[DayReport]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(reports)?)?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<DayReport>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::first::Day01;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_from_samples() {
+        let ms = Duration::from_millis;
+        let timing = Timing::from_samples(vec![ms(5), ms(1), ms(9), ms(3), ms(7)]).unwrap();
+        assert_eq!(
+            timing,
+            Timing {
+                runs: 5,
+                min: ms(1),
+                median: ms(5),
+                max: ms(9),
+            }
+        );
+        assert_eq!(Timing::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let [parse, part1, part2] = bench_solution::<Day01>("3   4\n4   3\n", 3).unwrap();
+        assert_eq!(part2.runs, 3);
+        assert!(part1.min <= part1.median && part1.median <= part1.max);
+        let reports = vec![DayReport {
+            day: 1,
+            parse,
+            part1,
+            part2,
+        }];
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench").join("results.json");
+        save(&path, &reports).unwrap();
+        assert_eq!(load(&path).unwrap(), reports);
+        assert!(time(0, || Ok(())).is_err());
+    }
+}
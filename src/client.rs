@@ -0,0 +1,206 @@
+use crate::error::{Error, Result};
+use log::debug;
+use reqwest::blocking;
+use reqwest::header::{COOKIE, USER_AGENT};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2024;
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+// AoC asks automated tools to identify themselves
+const AGENT: &str = "github.com/garry-cairns/adventofcode2024";
+
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+    http: blocking::Client,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            cache_dir: cache_dir.into(),
+            http: blocking::Client::new(),
+        }
+    }
+
+    // Session comes from $AOC_SESSION, falling back to ~/.config/aoc/session.
+    // A missing session is only an error once we actually need the network.
+    pub fn from_env(cache_dir: impl Into<PathBuf>) -> Self {
+        let session = session_from(env::var(SESSION_VAR).ok(), config_path().as_deref());
+        Client::new(BASE_URL, session, cache_dir)
+    }
+
+    pub fn input_url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}/input", self.base_url)
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{day:02}.txt"))
+    }
+
+    pub fn answer_url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}/answer", self.base_url)
+    }
+
+    pub fn answers_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{day:02}.answers"))
+    }
+
+    pub fn puzzle_url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}", self.base_url)
+    }
+
+    pub fn puzzle_path(&self, day: u8) -> PathBuf {
+        self.cache_dir.join(format!("day{day:02}.html"))
+    }
+
+    pub fn input(&self, day: u8) -> Result<String> {
+        self.cached(&self.input_url(day), &self.input_path(day))
+    }
+
+    // Only complete once part 1 is solved, since part 2 is hidden until then
+    pub fn puzzle(&self, day: u8) -> Result<String> {
+        self.cached(&self.puzzle_url(day), &self.puzzle_path(day))
+    }
+
+    // Where the input is cached, downloading it first if it isn't yet, so it
+    // can be read without going through a String
+    pub fn input_file(&self, day: u8) -> Result<PathBuf> {
+        let path = self.input_path(day);
+        if !is_cached(&path) {
+            self.download(&self.input_url(day), &path)?;
+        }
+        Ok(path)
+    }
+
+    // Hits the network at most once per file; after that the cached copy wins
+    fn cached(&self, url: &str, path: &Path) -> Result<String> {
+        if is_cached(path) {
+            debug!("Using cached copy {}", path.display());
+            return Ok(fs::read_to_string(path)?);
+        }
+        self.download(url, path)
+    }
+
+    fn download(&self, url: &str, path: &Path) -> Result<String> {
+        let body = self.get(url)?;
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(path, &body)?;
+        debug!("Cached {url} at {}", path.display());
+        Ok(body)
+    }
+
+    // Returns the HTML page AoC renders in reply, for submit::parse_verdict
+    pub fn post_answer(&self, day: u8, part: u8, answer: &str) -> Result<String> {
+        let url = self.answer_url(day);
+        let form = [("level", part.to_string()), ("answer", answer.to_string())];
+        self.send(&url, self.http.post(&url).form(&form))
+    }
+
+    fn get(&self, url: &str) -> Result<String> {
+        self.send(url, self.http.get(url))
+    }
+
+    fn send(&self, url: &str, request: blocking::RequestBuilder) -> Result<String> {
+        let session = self.session.as_ref().ok_or(Error::MissingSession)?;
+        debug!("URL is {url}");
+        let response = request
+            .header(COOKIE, format!("session={session}"))
+            .header(USER_AGENT, AGENT)
+            .send()?;
+        let status = response.status();
+        debug!("Status is {status}");
+        if status.is_success() {
+            Ok(response.text()?)
+        } else {
+            Err(Error::Http {
+                url: url.to_string(),
+                status,
+            })
+        }
+    }
+}
+
+// An empty file is a slot left by `aoc new-day`, not a download
+fn is_cached(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+fn config_path() -> Option<PathBuf> {
+    match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) => Some(PathBuf::from(dir)),
+        Err(_) => env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config")),
+    }
+    .map(|dir| dir.join("aoc").join("session"))
+}
+
+fn session_from(env_value: Option<String>, config: Option<&Path>) -> Option<String> {
+    env_value
+        .or_else(|| config.and_then(|path| fs::read_to_string(path).ok()))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::mock;
+    use std::io::Write;
+    use tempfile::{tempdir, NamedTempFile};
+
+    #[test]
+    fn test_input_is_fetched_once() {
+        let m = mock("GET", "/2024/day/1/input")
+            .match_header("cookie", "session=abc123")
+            .with_status(200)
+            .with_body("3   4\n4   3\n")
+            .expect(1)
+            .create();
+        let cache = tempdir().unwrap();
+        let client = Client::new(
+            &mockito::server_url(),
+            Some("abc123".to_string()),
+            cache.path(),
+        );
+
+        assert_eq!(client.input(1).unwrap(), "3   4\n4   3\n");
+        assert_eq!(client.input(1).unwrap(), "3   4\n4   3\n");
+        assert!(cache.path().join("day01.txt").exists());
+        m.assert();
+    }
+
+    #[test]
+    fn test_failed_fetch_is_not_cached() {
+        let _m = mock("GET", "/2024/day/2/input").with_status(400).create();
+        let cache = tempdir().unwrap();
+        let client = Client::new(
+            &mockito::server_url(),
+            Some("expired".to_string()),
+            cache.path(),
+        );
+
+        assert!(matches!(client.input(2), Err(Error::Http { status, .. }) if status == 400));
+        assert!(!cache.path().join("day02.txt").exists());
+    }
+
+    #[test]
+    fn test_session_from() {
+        let mut config = NamedTempFile::new().unwrap();
+        writeln!(config, "fromfile").unwrap();
+
+        let from_env = session_from(Some("fromenv".to_string()), Some(config.path()));
+        assert_eq!(from_env.as_deref(), Some("fromenv"));
+        let from_file = session_from(None, Some(config.path()));
+        assert_eq!(from_file.as_deref(), Some("fromfile"));
+        assert_eq!(session_from(None, None), None);
+    }
+}
@@ -1,36 +1,50 @@
-#[path = "utils.rs"]
-mod utils;
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::utils::{self, Grid};
 use itertools::Itertools;
-use ndarray::Array2;
+use log::debug;
 use std::collections::{HashMap, HashSet};
 
-pub fn detect_antinodes(input: &str) -> u32 {
-    let (grid, coords) = clean(input);
-    let mut antinodes: HashSet<utils::CoOrd> = HashSet::new();
-    // These would seem to be the same
-    let (height, _width) = (grid.shape()[0], grid.shape()[1]);
-    // Feels like the algo here should be:
-    // Find distinct chars in grid and make them a key in a hashmap: done
-    // For each char list all combinations of co-ord pairs: done
-    // Iterate over the keys: done
-    // Iterate over the pairs for that key: done
-    // Find the distance between the pair: done
-    // Check whether a point in each direction at the same distance will be in bounds
-    // +1 to the total for each one that is
-    coords
-        .values()
-        .flat_map(|set| set.iter())
-        .for_each(|value| {
-            antinodes.extend(local_antinodes(value, height, true));
-        });
-    antinodes.len() as u32
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = (Grid<char>, AntennaPairs);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        clean(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (grid, coords) = input;
+        Ok(count_antinodes(grid, coords, true).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (grid, coords) = input;
+        Ok(count_antinodes(grid, coords, false).into())
+    }
 }
 
-pub fn detect_antinodes_greedy(input: &str) -> u32 {
-    let (grid, coords) = clean(input);
+pub fn detect_antinodes(input: &str) -> Result<u32> {
+    let (grid, coords) = Day08::parse(input)?;
+    Ok(count_antinodes(&grid, &coords, true))
+}
+
+pub fn detect_antinodes_greedy(input: &str) -> Result<u32> {
+    let (grid, coords) = Day08::parse(input)?;
+    Ok(count_antinodes(&grid, &coords, false))
+}
+
+fn count_antinodes(grid: &Grid<char>, coords: &AntennaPairs, restrict_freq: bool) -> u32 {
+    antinodes(grid, coords, restrict_freq).len() as u32
+}
+
+pub fn antinodes(
+    grid: &Grid<char>,
+    coords: &AntennaPairs,
+    restrict_freq: bool,
+) -> HashSet<utils::CoOrd> {
     let mut antinodes: HashSet<utils::CoOrd> = HashSet::new();
-    // These would seem to be the same
-    let (height, _width) = (grid.shape()[0], grid.shape()[1]);
     // Feels like the algo here should be:
     // Find distinct chars in grid and make them a key in a hashmap: done
     // For each char list all combinations of co-ord pairs: done
@@ -43,67 +57,51 @@ pub fn detect_antinodes_greedy(input: &str) -> u32 {
         .values()
         .flat_map(|set| set.iter())
         .for_each(|value| {
-            antinodes.extend(local_antinodes(value, height, false));
+            antinodes.extend(local_antinodes(value, grid.size(), restrict_freq));
         });
-    antinodes.len() as u32
+    antinodes
 }
 
-fn clean(
-    input: &str,
-) -> (
-    Array2<char>,
-    HashMap<char, Vec<(utils::CoOrd, utils::CoOrd)>>,
-) {
-    let grid = utils::vec_to_array2(utils::string_to_2d_array(input, utils::just_chars));
-    let mut antennas: HashSet<char> = input.chars().collect();
-    println!("There are {:?}", &antennas.len());
-    let mut coords: HashMap<char, Vec<(utils::CoOrd, utils::CoOrd)>> = HashMap::new();
+// Every pair of co-ords sharing an antenna frequency
+pub type AntennaPairs = HashMap<char, Vec<(utils::CoOrd, utils::CoOrd)>>;
+
+fn clean(input: &str) -> Result<(Grid<char>, AntennaPairs)> {
+    let grid = Grid::parse(input)?;
+    let mut antennas: HashSet<char> = grid.iter().map(|(_, &c)| c).collect();
+    debug!("There are {} distinct characters", antennas.len());
+    let mut coords: AntennaPairs = HashMap::new();
     antennas.remove(&'.');
-    antennas.remove(&'\n');
     for antenna in antennas {
-        let (row_matches, column_matches) = utils::locate_all_in_grid(&grid, &antenna);
-        let mut all_coords: HashSet<utils::CoOrd> = HashSet::new();
-        for value in row_matches.values() {
-            all_coords.extend(value.clone());
-        }
-        for value in column_matches.values() {
-            all_coords.extend(value.clone());
-        }
         coords.insert(
             antenna,
-            all_coords
-                .iter()
-                .combinations(2)
-                .filter(|pair| pair[0] != pair[1])
-                .map(|pair| (pair[0].clone(), pair[1].clone()))
+            grid.find_all(&antenna)
+                .into_iter()
+                .tuple_combinations()
                 .collect(),
         );
     }
-    (grid, coords)
+    Ok((grid, coords))
 }
 
 fn local_antinodes(
     pair: &(utils::CoOrd, utils::CoOrd),
-    max: usize,
+    grid_size: (usize, usize),
     restrict_freq: bool,
 ) -> Vec<utils::CoOrd> {
     let mut result: Vec<utils::CoOrd> = Vec::new();
-    let mut line = utils::Line::new(pair.0, pair.1);
+    let line = utils::Line::new(pair.0, pair.1);
 
     if restrict_freq {
-        let (back, front) = line.extend((max, max));
-        if back.is_some() {
-            result.push(back.unwrap());
+        let (back, front) = line.extend(grid_size);
+        if let Some(point) = back {
+            result.push(point);
         }
-        if front.is_some() {
-            result.push(front.unwrap());
+        if let Some(point) = front {
+            result.push(point);
         }
     } else {
-        let (d_i, d_j) = utils::distance_between(pair.0, pair.1);
-        let back = line.extend_back_greedy(d_i, d_j, (max, max));
-        let forward = line.extend_forward_greedy(d_i, d_j, (max, max));
-        result.extend(back);
-        result.extend(forward);
+        result.extend(line.extend_back_greedy(grid_size));
+        result.extend(line.extend_forward_greedy(grid_size));
     }
     result
 }
@@ -127,7 +125,7 @@ mod tests {
 ............
 ............"#;
 
-        let result = detect_antinodes(input);
+        let result = detect_antinodes(input).unwrap();
         assert_eq!(result, 14);
     }
 
@@ -146,37 +144,7 @@ mod tests {
 ............
 ............"#;
 
-        let result = detect_antinodes_greedy(input);
+        let result = detect_antinodes_greedy(input).unwrap();
         assert_eq!(result, 34);
     }
 }
-
-/*
-......#....#
-...#....0...
-....#0....#.
-..#....0....
-....0....#..
-.#....A.....
-...#........
-#......#....
-........A...
-.........A..
-..........#.
-..........#.
-*/
-
-/*
-vv....v....v
-.v.v....0...
-..v.v0....v.
-..vv...0....
-....0....v..
-.v...vA....v
-...v..v.....
-v....v.v....
-..v.....A...
-....v....A..
-.v........v.
-...v......vv
-*/
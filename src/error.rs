@@ -0,0 +1,106 @@
+use reqwest::StatusCode;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+#[derive(Debug)]
+pub enum Error {
+    // The server answered, but not with a 2xx
+    Http {
+        url: String,
+        status: StatusCode,
+    },
+    // We never got an answer at all
+    Request(reqwest::Error),
+    Io(io::Error),
+    Json(serde_json::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    Parse(String),
+    // A parse error we can point at, both 1-based
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    InvalidInput(String),
+    // A scaffolded day whose part hasn't been written yet
+    Unsolved(u8, u8),
+    MissingSession,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Http { url, status } => write!(f, "{url} returned {status}"),
+            Error::Request(e) => write!(f, "request failed: {e}"),
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Json(e) => write!(f, "invalid JSON: {e}"),
+            Error::Png(e) => write!(f, "could not encode PNG: {e}"),
+            Error::Gif(e) => write!(f, "could not encode GIF: {e}"),
+            Error::Parse(message) => write!(f, "could not parse input: {message}"),
+            Error::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "line {line}, column {column}: {message}"),
+            Error::InvalidInput(message) => write!(f, "invalid puzzle input: {message}"),
+            Error::Unsolved(day, part) => write!(f, "day {day} part {part} is not solved yet"),
+            Error::MissingSession => write!(
+                f,
+                "no session cookie: set AOC_SESSION or write it to ~/.config/aoc/session"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Request(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Png(e) => Some(e),
+            Error::Gif(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    fn from(e: png::EncodingError) -> Self {
+        Error::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(e: gif::EncodingError) -> Self {
+        Error::Gif(e)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Request(e)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Error::Parse(e.to_string())
+    }
+}
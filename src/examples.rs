@@ -0,0 +1,200 @@
+use crate::error::{Error, Result};
+use crate::registry;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Fixtures are committed alongside the code, one directory per day
+pub const FIXTURE_DIR: &str = "fixtures";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Example {
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+// Each part of a puzzle page is its own <article>. The example is the first
+// <pre><code> block in it (part 2 usually reuses part 1's) and the expected
+// answer is the last emphasised <code> in it.
+pub fn extract(html: &str) -> Vec<Example> {
+    let article_rx = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let block_rx = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    let answer_rx = Regex::new(r"(?s)<code><em>(.*?)</em></code>").unwrap();
+
+    let mut examples: Vec<Example> = Vec::new();
+    for (i, article) in article_rx.captures_iter(html).enumerate() {
+        let body = &article[1];
+        let input = block_rx
+            .captures(body)
+            .map(|c| unescape(&c[1]))
+            .or_else(|| examples.last().map(|e| e.input.clone()));
+        let answer = answer_rx
+            .captures_iter(body)
+            .last()
+            .map(|c| unescape(&c[1]));
+        if let (Some(input), Some(answer)) = (input, answer) {
+            examples.push(Example {
+                part: i as u8 + 1,
+                input,
+                answer,
+            });
+        }
+    }
+    examples
+}
+
+fn unescape(fragment: &str) -> String {
+    let tag_rx = Regex::new(r"<[^>]+>").unwrap();
+    tag_rx
+        .replace_all(fragment, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn fixture_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}"))
+}
+
+pub fn write_fixtures(dir: &Path, day: u8, examples: &[Example]) -> Result<Vec<PathBuf>> {
+    let day_dir = fixture_path(dir, day);
+    fs::create_dir_all(&day_dir)?;
+    let mut written = Vec::new();
+    for example in examples {
+        let input = day_dir.join(format!("part{}.input", example.part));
+        let answer = day_dir.join(format!("part{}.answer", example.part));
+        fs::write(&input, &example.input)?;
+        fs::write(&answer, format!("{}\n", example.answer))?;
+        written.push(input);
+        written.push(answer);
+    }
+    Ok(written)
+}
+
+pub fn load_fixtures(dir: &Path, day: u8) -> Result<Vec<Example>> {
+    let day_dir = fixture_path(dir, day);
+    let mut examples = Vec::new();
+    for part in 1..=2 {
+        let input = day_dir.join(format!("part{part}.input"));
+        if !input.exists() {
+            continue;
+        }
+        let answer = fs::read_to_string(day_dir.join(format!("part{part}.answer")))?;
+        examples.push(Example {
+            part,
+            input: fs::read_to_string(input)?,
+            answer: answer.trim().to_string(),
+        });
+    }
+    Ok(examples)
+}
+
+// Runs a registered day against every fixture it has, panicking on a mismatch.
+// A day still being worked on isn't a failure: days without fixtures and parts
+// that aren't solved yet are skipped.
+pub fn assert_examples(dir: &Path, day: u8) {
+    let d = registry::find(day).unwrap_or_else(|| panic!("Day {day} is not registered"));
+    let examples = load_fixtures(dir, day).unwrap();
+    if examples.is_empty() {
+        eprintln!(
+            "Day {day} has no fixtures in {}; run `aoc examples --day {day}`",
+            fixture_path(dir, day).display()
+        );
+    }
+    for example in examples {
+        let answer = match (d.parts[usize::from(example.part) - 1])(&example.input) {
+            Ok(answer) => answer,
+            Err(Error::Unsolved(..)) => {
+                eprintln!("Day {day} part {} is not solved yet", example.part);
+                continue;
+            }
+            Err(e) => panic!("Day {day} part {}: {e}", example.part),
+        };
+        assert_eq!(
+            answer.to_string(),
+            example.answer,
+            "Day {day} part {}",
+            example.part
+        );
+    }
+}
+
+// Expands to one #[test] per day, each checking that day against its fixtures.
+// Feed it the registry with `registered_days!(example_tests)`.
+#[macro_export]
+macro_rules! example_tests {
+    ($(day!($number:expr, $module:ident, $solution:ident)),* $(,)?) => {
+        $(
+            #[test]
+            fn $module() {
+                let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join($crate::examples::FIXTURE_DIR);
+                $crate::examples::assert_examples(&dir, $number);
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>In the example above, this is <code>2</code> and then <code><em>11</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>So, for the example above, the similarity score is <code><em>31</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_extract() {
+        let examples = extract(PAGE);
+        assert_eq!(
+            examples,
+            vec![
+                Example {
+                    part: 1,
+                    input: "3   4\n4   3\n".to_string(),
+                    answer: "11".to_string(),
+                },
+                Example {
+                    part: 2,
+                    input: "3   4\n4   3\n".to_string(),
+                    answer: "31".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("<em>a</em>&lt;b&gt; &amp; c"), "a<b> & c");
+    }
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let dir = tempdir().unwrap();
+        let examples = extract(PAGE);
+        write_fixtures(dir.path(), 1, &examples).unwrap();
+
+        assert_eq!(load_fixtures(dir.path(), 1).unwrap(), examples);
+        assert!(load_fixtures(dir.path(), 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_fixtures_are_skipped() {
+        let dir = tempdir().unwrap();
+        assert_examples(dir.path(), 1);
+    }
+}
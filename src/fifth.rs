@@ -1,8 +1,45 @@
-#[path = "utils.rs"]
-mod utils;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::utils::parse::{self, Line};
+use log::trace;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (RuleSet, Vec<Vec<u32>>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let [rule_section, page_section] = &parse::sections(input)[..] else {
+            return Err(Error::InvalidInput(
+                "expected rules and updates separated by a blank line".to_string(),
+            ));
+        };
+        let rules = RuleSet {
+            rules: rule_section
+                .iter()
+                .map(|&line| make_rules(line))
+                .collect::<Result<_>>()?,
+        };
+        let pages = page_section
+            .iter()
+            .map(|&line| extract_page_numbers(line))
+            .collect::<Result<_>>()?;
+        Ok((rules, pages))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (rules, pages) = input;
+        Ok(valid_total(pages, rules).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (rules, pages) = input;
+        Ok(corrected_pages_total(pages, rules).into())
+    }
+}
+
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 struct Rule {
     left: u32,
@@ -10,7 +47,7 @@ struct Rule {
 }
 
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-struct RuleSet {
+pub struct RuleSet {
     rules: Vec<Rule>,
 }
 
@@ -35,7 +72,7 @@ impl RuleSet {
         right_values
     }
 
-    fn sort_rules(&self, page: &Vec<u32>) -> Vec<u32> {
+    fn sort_rules(&self, page: &[u32]) -> Vec<u32> {
         let mut ordering: HashMap<usize, u32> = HashMap::new();
         let mut result: Vec<u32> = Vec::new();
         for item in page {
@@ -52,44 +89,46 @@ impl RuleSet {
         for key in keys {
             result.push(*ordering.get(&key).unwrap());
         }
-        println!(
+        trace!(
             "Page: {:?}, Sorted: {:?}, Ordering: {:?}",
-            page, &result, &ordering
+            page,
+            &result,
+            &ordering
         );
         result
     }
 }
 
-pub fn pageorderingtotal(input: &str) -> u32 {
-    let sections: Vec<&str> = input.split("\n\n").collect();
-    let rules = RuleSet {
-        rules: utils::string_to_2d_array(sections[0], make_rules).concat(),
-    };
-    let pages = utils::string_to_2d_array(sections[1], extract_page_numbers);
-    pages.iter().fold(0, |acc, x| acc + is_valid(&x, &rules))
+pub fn pageorderingtotal(input: &str) -> Result<u32> {
+    let (rules, pages) = Day05::parse(input)?;
+    Ok(valid_total(&pages, &rules))
+}
+
+pub fn corrected_total(input: &str) -> Result<u32> {
+    let (rules, pages) = Day05::parse(input)?;
+    Ok(corrected_pages_total(&pages, &rules))
+}
+
+fn valid_total(pages: &[Vec<u32>], rules: &RuleSet) -> u32 {
+    pages.iter().fold(0, |acc, x| acc + is_valid(x, rules))
 }
 
-pub fn corrected_total(input: &str) -> u32 {
-    let sections: Vec<&str> = input.split("\n\n").collect();
-    let rules = RuleSet {
-        rules: utils::string_to_2d_array(sections[0], make_rules).concat(),
-    };
-    let pages = utils::string_to_2d_array(sections[1], extract_page_numbers);
-    pages.iter().fold(0, |acc, x| acc + valid_perm(&x, &rules))
+fn corrected_pages_total(pages: &[Vec<u32>], rules: &RuleSet) -> u32 {
+    pages.iter().fold(0, |acc, x| acc + valid_perm(x, rules))
 }
 
-fn valid_perm(page: &Vec<u32>, rules: &RuleSet) -> u32 {
+fn valid_perm(page: &[u32], rules: &RuleSet) -> u32 {
     if is_valid(page, rules) != 0 {
-        return 0;
+        0
     } else {
-        let mut relevant = relevant_rules(page, rules);
+        let relevant = relevant_rules(page, rules);
         let sorted = &relevant.sort_rules(page);
-        return sorted[sorted.len() / 2];
+        sorted[sorted.len() / 2]
     }
 }
 
-fn is_valid(page: &Vec<u32>, rules: &RuleSet) -> u32 {
-    let relevant = relevant_rules(page, &rules);
+fn is_valid(page: &[u32], rules: &RuleSet) -> u32 {
+    let relevant = relevant_rules(page, rules);
     for (i, key) in page.iter().enumerate() {
         let lefts = relevant.lefts(*key);
         let left_set = HashSet::from_iter(&page[..i]);
@@ -105,20 +144,16 @@ fn is_valid(page: &Vec<u32>, rules: &RuleSet) -> u32 {
     page[page.len() / 2]
 }
 
-fn extract_page_numbers(original: &str) -> Vec<u32> {
-    original.split(",").map(|s| s.parse().unwrap()).collect()
+fn extract_page_numbers(original: Line) -> Result<Vec<u32>> {
+    parse::delimited(original, ',')
 }
 
-fn make_rules(original: &str) -> Vec<Rule> {
-    let parsed: Vec<u32> = original.split("|").map(|s| s.parse().unwrap()).collect();
-    [Rule {
-        left: parsed[0],
-        right: parsed[1],
-    }]
-    .to_vec()
+fn make_rules(original: Line) -> Result<Rule> {
+    let (left, right) = parse::pair(original, '|')?;
+    Ok(Rule { left, right })
 }
 
-fn relevant_rules(page: &Vec<u32>, rules: &RuleSet) -> RuleSet {
+fn relevant_rules(page: &[u32], rules: &RuleSet) -> RuleSet {
     let mut relevant: Vec<Rule> = Vec::new();
 
     for rule in &rules.rules {
@@ -165,7 +200,7 @@ mod tests {
 61,13,29
 97,13,75,29,47"#;
 
-        let result = pageorderingtotal(input);
+        let result = pageorderingtotal(input).unwrap();
         assert_eq!(result, 143);
     }
 
@@ -200,7 +235,7 @@ mod tests {
 61,13,29
 97,13,75,29,47"#;
 
-        let result = corrected_total(input);
+        let result = corrected_total(input).unwrap();
         assert_eq!(result, 123);
     }
 }
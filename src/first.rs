@@ -1,56 +1,219 @@
-#[path = "utils.rs"]
-mod utils;
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::utils::parse::{self, Line};
+use itertools::Itertools;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
 
-pub fn check_distance(input: &str) -> u32 {
-    let cleaned: (Vec<u32>, Vec<u32>) = string_to_2d_array(input);
-    let differences: Vec<u32> = cleaned
-        .0
-        .iter()
-        .zip(cleaned.1.iter())
-        .map(|(&x, &y)| difference(x, y))
-        .collect();
-    differences.iter().sum()
+pub struct Day01;
+
+impl Solution for Day01 {
+    // The two lists, in the order they were written down
+    type Input = Vec<Vec<u32>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        read_lists(input.as_bytes(), Some(2))
+    }
+
+    fn parse_reader<R: BufRead>(reader: R) -> Result<Self::Input> {
+        read_lists(reader, Some(2))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(distance(&input[0], &input[1]).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(similarity(&input[0], &input[1]).into())
+    }
 }
 
-pub fn check_similarity(input: &str) -> u32 {
-    let cleaned: (Vec<u32>, Vec<u32>) = string_to_2d_array(input);
-    let frequency = count_occurrences(cleaned.1);
-    cleaned
-        .0
-        .iter()
-        .fold(0, |acc, &x| acc + x * frequency.get(&x).unwrap_or(&0))
+pub fn check_distance(input: &str) -> Result<u64> {
+    Day01::parse(input).map(|lists| distance(&lists[0], &lists[1]))
+}
+
+pub fn check_similarity(input: &str) -> Result<u64> {
+    Day01::parse(input).map(|lists| similarity(&lists[0], &lists[1]))
+}
+
+// Ways of scoring one list against another
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Metric {
+    // Part 1: pair the lists up smallest to largest and add up the gaps
+    Distance,
+    // Part 2: each ID on the left times how often it's on the right
+    Similarity,
+    // Rank correlations, pairing the lists row by row
+    Spearman,
+    Kendall,
+    // How many IDs the lists share, counting repeats
+    Intersection,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 5] = [
+        Metric::Distance,
+        Metric::Similarity,
+        Metric::Spearman,
+        Metric::Kendall,
+        Metric::Intersection,
+    ];
+
+    pub fn score(self, left: &[u32], right: &[u32]) -> Score {
+        match self {
+            Metric::Distance => Score::Count(distance(left, right)),
+            Metric::Similarity => Score::Count(similarity(left, right)),
+            Metric::Spearman => Score::Correlation(spearman(left, right)),
+            Metric::Kendall => Score::Correlation(kendall(left, right)),
+            Metric::Intersection => Score::Count(intersection(left, right)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Score {
+    Count(u64),
+    // None when a list has no spread to rank, e.g. every ID the same
+    Correlation(Option<f64>),
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Count(n) => write!(f, "{n}"),
+            Score::Correlation(Some(r)) => write!(f, "{r:.4}"),
+            Score::Correlation(None) => write!(f, "undefined"),
+        }
+    }
+}
+
+// Scores every pair of lists, first against second, first against third, and
+// so on, with the indices of the pair
+pub fn compare(lists: &[Vec<u32>], metric: Metric) -> Vec<((usize, usize), Score)> {
+    (0..lists.len())
+        .tuple_combinations()
+        .map(|(a, b)| ((a, b), metric.score(&lists[a], &lists[b])))
+        .collect()
+}
+
+pub fn distance(left: &[u32], right: &[u32]) -> u64 {
+    left.iter()
+        .sorted_unstable()
+        .zip(right.iter().sorted_unstable())
+        .map(|(&x, &y)| u64::from(x.abs_diff(y)))
+        .sum()
 }
 
-fn manipulate_input(original: &str) -> (u32, u32) {
-    let mut updated = original.split_whitespace();
+pub fn similarity(left: &[u32], right: &[u32]) -> u64 {
+    let frequency = count_occurrences(right.iter().copied());
+    left.iter()
+        .map(|x| u64::from(*x) * u64::from(*frequency.get(x).unwrap_or(&0)))
+        .sum()
+}
 
-    let first_num = updated
-        .next()
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(0);
-    let second_num = updated
-        .next()
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(0);
+pub fn intersection(left: &[u32], right: &[u32]) -> u64 {
+    let frequency = count_occurrences(right.iter().copied());
+    count_occurrences(left.iter().copied())
+        .iter()
+        .map(|(x, &n)| u64::from(n.min(*frequency.get(x).unwrap_or(&0))))
+        .sum()
+}
+
+// Pearson's correlation of the two lists' ranks, ties sharing their average rank
+pub fn spearman(left: &[u32], right: &[u32]) -> Option<f64> {
+    let n = left.len().min(right.len());
+    let (x, y) = (ranks(&left[..n]), ranks(&right[..n]));
+    let mean = (n as f64 + 1.0) / 2.0;
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(&y) {
+        covariance += (a - mean) * (b - mean);
+        var_x += (a - mean).powi(2);
+        var_y += (b - mean).powi(2);
+    }
+    let spread = (var_x * var_y).sqrt();
+    (spread > 0.0).then(|| covariance / spread)
+}
 
-    (first_num, second_num)
+// Kendall's tau-b, which allows for ties in either list. Checks every pair of
+// rows, so it's quadratic in the length of the lists.
+pub fn kendall(left: &[u32], right: &[u32]) -> Option<f64> {
+    let rows: Vec<(u32, u32)> = left.iter().copied().zip(right.iter().copied()).collect();
+    let (mut concordant, mut discordant) = (0i64, 0i64);
+    let (mut tied_x, mut tied_y) = (0i64, 0i64);
+    for ((x1, y1), (x2, y2)) in rows.iter().tuple_combinations() {
+        match (x1.cmp(x2), y1.cmp(y2)) {
+            (Ordering::Equal, Ordering::Equal) => {
+                tied_x += 1;
+                tied_y += 1;
+            }
+            (Ordering::Equal, _) => tied_x += 1,
+            (_, Ordering::Equal) => tied_y += 1,
+            (a, b) if a == b => concordant += 1,
+            _ => discordant += 1,
+        }
+    }
+    let pairs = (rows.len() * rows.len().saturating_sub(1) / 2) as i64;
+    let spread = (((pairs - tied_x) * (pairs - tied_y)) as f64).sqrt();
+    (spread > 0.0).then(|| (concordant - discordant) as f64 / spread)
 }
 
-fn string_to_2d_array(input: &str) -> (Vec<u32>, Vec<u32>) {
-    let initial: Vec<(u32, u32)> = input.lines().map(|line| manipulate_input(line)).collect();
-    let mut first_column: Vec<u32> = initial.iter().map(|(x, _)| *x).collect();
-    let mut second_column: Vec<u32> = initial.iter().map(|(_, y)| *y).collect();
-    first_column.sort();
-    second_column.sort();
-    (first_column, second_column)
+// 1-based ranks in the list's own order
+fn ranks(list: &[u32]) -> Vec<f64> {
+    let order: Vec<usize> = (0..list.len()).sorted_by_key(|&i| list[i]).collect();
+    let mut ranks = vec![0.0; list.len()];
+    let mut position = 0;
+    for run in order.chunk_by(|&a, &b| list[a] == list[b]) {
+        let rank = position as f64 + (run.len() as f64 + 1.0) / 2.0;
+        for &i in run {
+            ranks[i] = rank;
+        }
+        position += run.len();
+    }
+    ranks
 }
 
-fn difference(first: u32, second: u32) -> u32 {
-    first.abs_diff(second)
+// Reads the lists a line at a time, so a file read through parse_reader is
+// never held in memory as a whole. Any whitespace may separate the IDs, lines
+// may end in CRLF, and blank lines are skipped. Every line has to have `width`
+// IDs, or as many as the first line when no width is given; anything else is
+// an error naming its line.
+pub fn read_lists<R: BufRead>(mut reader: R, width: Option<usize>) -> Result<Vec<Vec<u32>>> {
+    let mut lists: Vec<Vec<u32>> = width.map_or_else(Vec::new, |w| vec![Vec::new(); w]);
+    let mut buffer = String::new();
+    for number in 1.. {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            break;
+        }
+        let line = Line {
+            number,
+            text: buffer.trim_end_matches(['\n', '\r']),
+        };
+        if line.is_blank() {
+            continue;
+        }
+        let ids: Vec<u32> = parse::words(line)?;
+        if lists.is_empty() {
+            lists.resize(ids.len(), Vec::new());
+        }
+        if ids.len() != lists.len() {
+            return Err(line.error(
+                line.text,
+                format!("expected {} location IDs, found {}", lists.len(), ids.len()),
+            ));
+        }
+        for (list, id) in lists.iter_mut().zip(ids) {
+            list.push(id);
+        }
+    }
+    Ok(lists)
 }
 
-fn count_occurrences<T: Eq + std::hash::Hash>(items: Vec<T>) -> HashMap<T, u32> {
+fn count_occurrences<T: Eq + std::hash::Hash>(
+    items: impl IntoIterator<Item = T>,
+) -> HashMap<T, u32> {
     let mut counter = HashMap::new();
 
     for item in items {
@@ -63,6 +226,7 @@ fn count_occurrences<T: Eq + std::hash::Hash>(items: Vec<T>) -> HashMap<T, u32>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn test_check_distance() {
@@ -72,7 +236,7 @@ mod tests {
 1   3
 3   9
 3   3"#;
-        let result = check_distance(input);
+        let result = check_distance(input).unwrap();
         assert_eq!(result, 11);
     }
 
@@ -84,7 +248,92 @@ mod tests {
 1   3
 3   9
 3   3"#;
-        let result = check_similarity(input);
+        let result = check_similarity(input).unwrap();
         assert_eq!(result, 31);
     }
+
+    #[test]
+    fn test_read_lists() {
+        let input = "3   4\r\n4\t3\r\n\r\n 2 5 \r\n1   3\n3   9\n3   3\n\n";
+        let lists = read_lists(input.as_bytes(), Some(2)).unwrap();
+        assert_eq!(lists, vec![vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3]]);
+
+        let wide = read_lists("1 2 3\n4 5 6\n".as_bytes(), None).unwrap();
+        assert_eq!(wide, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day01.txt");
+        std::fs::write(&path, "3   4\n4   3\n2   5\n").unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+        let lists = Day01::parse_reader(std::io::BufReader::new(file)).unwrap();
+        assert_eq!(lists, vec![vec![3, 4, 2], vec![4, 3, 5]]);
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        let header = read_lists("left right\n3   4\n".as_bytes(), Some(2)).unwrap_err();
+        assert!(matches!(
+            header,
+            Error::Syntax {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+        let missing = read_lists("3   4\r\n4\r\n".as_bytes(), None).unwrap_err();
+        assert_eq!(
+            missing.to_string(),
+            "line 2, column 1: expected 2 location IDs, found 1"
+        );
+        let negative = read_lists("3   4\n4   -3\n".as_bytes(), Some(2)).unwrap_err();
+        assert!(matches!(
+            negative,
+            Error::Syntax {
+                line: 2,
+                column: 5,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_metrics() {
+        let (left, right) = ([3, 4, 2, 1, 3, 3], [4, 3, 5, 3, 9, 3]);
+        assert_eq!(Metric::Distance.score(&left, &right), Score::Count(11));
+        assert_eq!(Metric::Similarity.score(&left, &right), Score::Count(31));
+        // The 3 three times on both sides and nothing else in common but the 4
+        assert_eq!(Metric::Intersection.score(&left, &right), Score::Count(4));
+
+        let ranked = [1, 2, 3, 4, 5];
+        assert_eq!(spearman(&ranked, &[10, 20, 30, 40, 50]), Some(1.0));
+        assert_eq!(kendall(&ranked, &[5, 4, 3, 2, 1]), Some(-1.0));
+        assert_eq!(spearman(&ranked, &[7, 7, 7, 7, 7]), None);
+        // One swapped neighbour: 1 - 6 * 2 / (5 * 24), and 8 of 10 pairs agree
+        let swapped = [1, 2, 4, 3, 5];
+        assert!((spearman(&ranked, &swapped).unwrap() - 0.9).abs() < 1e-9);
+        assert!((kendall(&ranked, &swapped).unwrap() - 0.8).abs() < 1e-9);
+        // Ties share their average rank, so tied rows still line up exactly
+        let tied = [1, 2, 2, 3];
+        assert!((spearman(&tied, &tied).unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(ranks(&[5, 1, 5, 3]), vec![3.5, 1.0, 3.5, 2.0]);
+    }
+
+    #[test]
+    fn test_compare() {
+        let lists = vec![vec![1, 2, 3], vec![3, 2, 1], vec![1, 2, 3]];
+        let scores = compare(&lists, Metric::Kendall);
+        assert_eq!(
+            scores,
+            vec![
+                ((0, 1), Score::Correlation(Some(-1.0))),
+                ((0, 2), Score::Correlation(Some(1.0))),
+                ((1, 2), Score::Correlation(Some(-1.0))),
+            ]
+        );
+        assert_eq!(Score::Correlation(None).to_string(), "undefined");
+        assert_eq!(Score::Correlation(Some(0.5)).to_string(), "0.5000");
+    }
 }
@@ -1,31 +1,42 @@
-#[path = "utils.rs"]
-mod utils;
-use std::collections::HashSet;
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::utils::{CoOrd, Direction, Grid, Vec2};
 
-pub fn wordsearch(input: &str) -> i32 {
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Grid::parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_words(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_crosses(input).into())
+    }
+}
+
+pub fn wordsearch(input: &str) -> Result<i32> {
+    Ok(count_words(&Day04::parse(input)?))
+}
+
+pub fn crosssearch(input: &str) -> Result<i32> {
+    Ok(count_crosses(&Day04::parse(input)?))
+}
+
+fn count_words(board: &Grid<char>) -> i32 {
     // backtracking algorithm
-    let board = utils::string_to_2d_array(input, utils::just_chars);
-    let n = board.len();
     let word = "XMAS";
     let mut count = 0;
 
-    let directions = vec![
-        (0, 1),   // right
-        (0, -1),  // left
-        (1, 0),   // down
-        (-1, 0),  // up
-        (1, 1),   // down-right
-        (1, -1),  // down-left
-        (-1, 1),  // up-right
-        (-1, -1), // up-left
-    ];
-
-    for i in 0..n {
-        for j in 0..n {
-            for &(dx, dy) in &directions {
-                if search(&board, word, i, j, dx, dy, 0) {
-                    count += 1;
-                }
+    for start in board.coords() {
+        for direction in Direction::ALL {
+            if search(board, word, Some(start), direction.delta(), 0) {
+                count += 1;
             }
         }
     }
@@ -33,102 +44,38 @@ pub fn wordsearch(input: &str) -> i32 {
     count
 }
 
-pub fn crosssearch(input: &str) -> i32 {
-    let board = utils::string_to_2d_array(input, utils::just_chars);
-    let n = board.len();
-    let word = "A";
+fn count_crosses(board: &Grid<char>) -> i32 {
     let mut count = 0;
 
-    let directions = vec![
-        (1, 1),   // down-right
-        (1, -1),  // down-left
-        (-1, 1),  // up-right
-        (-1, -1), // up-left
-    ];
-
-    // Starting by finding all the As to know where to centre searches
-    // Starting from 1 in because an A at any edge won't be able to make a cross
-    let centres = centre_matches(&board, &word, directions.clone());
-
-    for centre in centres {
-        let mut local_count = 0;
-        let three_square = subarray(&board, &centre);
-
-        for i in 0..n {
-            for j in 0..n {
-                for &(dx, dy) in &directions {
-                    if search(&three_square, "MAS", i, j, dx, dy, 0) {
-                        local_count += 1;
-                    }
-                }
-            }
-        }
-        if local_count == 2 {
+    // Each diagonal through an A has to read MAS one way or the other, so walk
+    // both diagonals from their top corners in whichever direction reaches the A
+    for centre in board.find_all(&'A') {
+        let reads_mas = |corner: Direction| {
+            let start = board.offset(centre, corner.delta());
+            let step = corner.reverse().delta();
+            search(board, "MAS", start, step, 0) || search(board, "SAM", start, step, 0)
+        };
+        if reads_mas(Direction::UpLeft) && reads_mas(Direction::UpRight) {
             count += 1;
         }
     }
     count
 }
 
-fn centre_matches(
-    board: &Vec<Vec<char>>,
-    word: &str,
-    directions: Vec<(isize, isize)>,
-) -> HashSet<(usize, usize)> {
-    let n = board.len();
-    let mut matches = HashSet::new();
-
-    for i in 1..n - 1 {
-        for j in 1..n - 1 {
-            for &(dx, dy) in &directions {
-                if search(&board, word, i, j, dx, dy, 0) {
-                    matches.insert((i, j));
-                }
-            }
-        }
-    }
-    matches
-}
-
-fn subarray(board: &Vec<Vec<char>>, centre: &(usize, usize)) -> Vec<Vec<char>> {
-    let (centre_x, centre_y) = centre;
-    (0..3)
-        .map(|i| {
-            (0..3)
-                .map(|j| board[centre_x - 1 + i][centre_y - 1 + j])
-                .collect()
-        })
-        .collect()
-}
-
-fn search(
-    board: &Vec<Vec<char>>,
-    word: &str,
-    x: usize,
-    y: usize,
-    dx: isize,
-    dy: isize,
-    index: usize,
-) -> bool {
+// True if `word` reads from `at` moving by `step` each letter. The grid hands
+// back None once a step leaves it, which ends the search.
+fn search(board: &Grid<char>, word: &str, at: Option<CoOrd>, step: Vec2, index: usize) -> bool {
     if index == word.len() {
         return true;
     }
-    if x >= board.len() || y >= board.len() {
+    let Some(at) = at else {
         return false;
-    }
-    if board[x][y] != word.chars().nth(index).unwrap() {
+    };
+    if board[at] != word.chars().nth(index).unwrap() {
         return false;
     }
 
-    search(
-        board,
-        word,
-        (x as isize + dx) as usize,
-        (y as isize + dy) as usize,
-        dx,
-        dy,
-        index + 1,
-    )
+    search(board, word, board.offset(at, step), step, index + 1)
 }
 
 #[cfg(test)]
@@ -148,7 +95,7 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX"#;
 
-        let result = wordsearch(input);
+        let result = wordsearch(input).unwrap();
         assert_eq!(result, 18);
     }
 
@@ -165,7 +112,7 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX"#;
 
-        let result = crosssearch(input);
+        let result = crosssearch(input).unwrap();
         assert_eq!(result, 9);
     }
 }
@@ -0,0 +1,20 @@
+pub mod bench;
+pub mod client;
+pub mod eighth;
+pub mod error;
+pub mod examples;
+pub mod fifth;
+pub mod first;
+pub mod fourth;
+pub mod ninth;
+pub mod recorder;
+pub mod registry;
+pub mod render;
+pub mod scaffold;
+pub mod second;
+pub mod seventh;
+pub mod sixth;
+pub mod solution;
+pub mod submit;
+pub mod third;
+pub mod utils;
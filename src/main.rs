@@ -1,9 +1,526 @@
-mod ninth;
-mod utils;
+use adventofcode2024::bench::{self, DayReport};
+use adventofcode2024::client::Client;
+use adventofcode2024::error::Result;
+use adventofcode2024::recorder::Recorder;
+use adventofcode2024::render::{self, Palette};
+use adventofcode2024::second::{self, SafetyPolicy};
+use adventofcode2024::solution::{Outcome, Solution};
+use adventofcode2024::utils::{CoOrd, Grid};
+use adventofcode2024::{eighth, examples, registry, scaffold, sixth, submit, utils};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-fn main() {
+// Puzzle inputs are cached here, one file per day
+const INPUT_DIR: &str = "inputs";
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2024 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single day's solver, or every registered day with --all
+    Run {
+        #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u8>,
+        /// Runs both parts when omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Defaults to inputs/dayNN.txt, downloading it if it isn't there yet
+        #[arg(long, conflicts_with = "all")]
+        input: Option<PathBuf>,
+        #[arg(long)]
+        all: bool,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Time parsing and each part over many runs, for one day or all of them
+    Bench {
+        /// Benchmarks every registered day when omitted
+        #[arg(long)]
+        day: Option<u8>,
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+        /// Also write the timings as JSON, to compare against a later run
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    /// Download a day's puzzle input into inputs/ unless it is already cached
+    Fetch {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Create, register and add an input slot for a new day's module
+    NewDay {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Pull the examples and expected answers out of a puzzle page into fixtures/
+    Examples {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Read a saved copy of the page instead of downloading it
+        #[arg(long)]
+        page: Option<PathBuf>,
+        /// Download the page again, e.g. once part 2 has been unlocked
+        #[arg(long, conflicts_with = "page")]
+        refresh: bool,
+    },
+    /// Draw a day's map with what a part found highlighted: day 6's guard route
+    /// (part 2: obstacles that make a loop) or day 8's antinodes
+    Render {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Write a .png or .svg instead of printing to the terminal
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Animate day 6's guard a stretch at a time; part 2 adds the first obstacle
+    /// that traps them in a loop
+    Replay {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// A .gif, or otherwise a directory to fill with numbered PNGs
+        #[arg(long)]
+        output: PathBuf,
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..=100))]
+        fps: u16,
+        /// Pixels along each side of a cell
+        #[arg(long, default_value_t = render::CELL_SIZE, value_parser = clap::value_parser!(u32).range(1..))]
+        cell_size: u32,
+    },
+    /// Say why each of day 2's reports is or isn't safe
+    Diagnose {
+        #[arg(long)]
+        day: u8,
+        /// Part 2 allows the dampener one removal
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// How many levels may be removed, instead of the part's own rule
+        #[arg(long)]
+        dampener: Option<usize>,
+        /// Write the verdicts as CSV instead of printing them
+        #[arg(long)]
+        csv: Option<PathBuf>,
+    },
+    /// Submit an answer, solving the part first if no answer is given
+    Submit {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        #[arg(long)]
+        answer: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    // One JSON object per line for each part run, errors included
+    Json,
+}
+
+#[derive(Serialize)]
+struct PartOutcome {
+    day: u8,
+    part: u8,
+    answer: Option<String>,
+    elapsed_ns: Option<u64>,
+    error: Option<String>,
+}
+
+fn main() -> ExitCode {
     env_logger::init();
-    let input = utils::file_input("./src/input.txt").unwrap();
-    let result = ninth::whole_files(&input);
-    println!("{result:?}");
+    let cli = Cli::parse();
+    let outcome = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            all,
+            format,
+        } => run(day, part, input, all, format),
+        Command::Bench { day, runs, save } => bench(day, runs as usize, save),
+        Command::Fetch { day } => fetch(day),
+        Command::NewDay { day } => new_day(day),
+        Command::Examples { day, page, refresh } => extract_examples(day, page, refresh),
+        Command::Render {
+            day,
+            part,
+            input,
+            output,
+        } => render(day, part, input, output),
+        Command::Replay {
+            day,
+            part,
+            input,
+            output,
+            fps,
+            cell_size,
+        } => replay(day, part, input, output, Recorder::new(fps, cell_size)),
+        Command::Diagnose {
+            day,
+            part,
+            input,
+            dampener,
+            csv,
+        } => diagnose(day, part, input, dampener, csv),
+        Command::Submit { day, part, answer } => submit(day, part, answer),
+    };
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    all: bool,
+    format: Format,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let days: Vec<&registry::Day> = if all {
+        registry::DAYS.iter().collect()
+    } else {
+        let number = day.unwrap();
+        let d = registry::find(number).ok_or(format!("Day {number} has no registered solver"))?;
+        vec![d]
+    };
+    let parts: Vec<u8> = part.map_or(vec![1, 2], |p| vec![p]);
+    // Keep going after a failure so --all reports on every day
+    let mut failed = false;
+    for d in days {
+        let mut reader = match open_input(d.number, input.as_ref()) {
+            Ok(reader) => reader,
+            Err(e) => {
+                failed = true;
+                if format == Format::Json {
+                    for &p in &parts {
+                        emit_json(PartOutcome {
+                            day: d.number,
+                            part: p,
+                            answer: None,
+                            elapsed_ns: None,
+                            error: Some(format!("could not load input: {e}")),
+                        })?;
+                    }
+                } else {
+                    eprintln!("Day {}: could not load input: {e}", d.number);
+                }
+                continue;
+            }
+        };
+        let outcomes = match (d.solve)(&mut reader, &parts) {
+            Ok(outcomes) => outcomes,
+            // Nothing got as far as running, so every part fails the same way
+            Err(e) => {
+                failed = true;
+                for &p in &parts {
+                    match format {
+                        Format::Text => eprintln!("Day {} part {p}: {e}", d.number),
+                        Format::Json => emit_json(PartOutcome {
+                            day: d.number,
+                            part: p,
+                            answer: None,
+                            elapsed_ns: None,
+                            error: Some(e.to_string()),
+                        })?,
+                    }
+                }
+                continue;
+            }
+        };
+        for Outcome {
+            part: p,
+            result,
+            elapsed,
+        } in outcomes
+        {
+            failed |= result.is_err();
+            match (format, result) {
+                (Format::Text, Ok(answer)) => println!("Day {} part {p}: {answer}", d.number),
+                (Format::Text, Err(e)) => eprintln!("Day {} part {p}: {e}", d.number),
+                (Format::Json, result) => emit_json(PartOutcome {
+                    day: d.number,
+                    part: p,
+                    answer: result.as_ref().ok().map(|a| a.to_string()),
+                    elapsed_ns: Some(elapsed.as_nanos() as u64),
+                    error: result.err().map(|e| e.to_string()),
+                })?,
+            }
+        }
+    }
+    if failed {
+        Err("one or more parts failed".into())
+    } else {
+        Ok(())
+    }
+}
+
+fn emit_json(outcome: PartOutcome) -> std::result::Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string(&outcome)?);
+    Ok(())
+}
+
+fn bench(
+    day: Option<u8>,
+    runs: usize,
+    save: Option<PathBuf>,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let days: Vec<&registry::Day> = match day {
+        Some(number) => {
+            vec![registry::find(number).ok_or(format!("Day {number} has no registered solver"))?]
+        }
+        None => registry::DAYS.iter().collect(),
+    };
+    let mut reports = Vec::new();
+    let mut failed = false;
+    for d in days {
+        let timings = load_input(d.number, None).and_then(|contents| (d.bench)(&contents, runs));
+        match timings {
+            Ok([parse, part1, part2]) => {
+                println!("Day {} parse:  {parse}", d.number);
+                println!("Day {} part 1: {part1}", d.number);
+                println!("Day {} part 2: {part2}", d.number);
+                reports.push(DayReport {
+                    day: d.number,
+                    parse,
+                    part1,
+                    part2,
+                });
+            }
+            Err(e) => {
+                eprintln!("Day {}: {e}", d.number);
+                failed = true;
+            }
+        }
+    }
+    if let Some(path) = save {
+        bench::save(&path, &reports)?;
+        println!("Saved timings to {}", path.display());
+    }
+    if failed {
+        Err("one or more days failed".into())
+    } else {
+        Ok(())
+    }
+}
+
+fn fetch(day: u8) -> std::result::Result<(), Box<dyn Error>> {
+    let client = Client::from_env(INPUT_DIR);
+    client.input(day)?;
+    println!("{}", client.input_path(day).display());
+    Ok(())
+}
+
+// Run from the repository root, since it edits the source tree
+fn new_day(day: u8) -> std::result::Result<(), Box<dyn Error>> {
+    let slot = Client::from_env(INPUT_DIR).input_path(day);
+    for path in scaffold::create(Path::new("."), day, &slot)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn extract_examples(
+    day: u8,
+    page: Option<PathBuf>,
+    refresh: bool,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let html = match page {
+        Some(path) => utils::file_input(&path.to_string_lossy())?,
+        None => {
+            let client = Client::from_env(INPUT_DIR);
+            if refresh && client.puzzle_path(day).exists() {
+                fs::remove_file(client.puzzle_path(day))?;
+            }
+            client.puzzle(day)?
+        }
+    };
+    let found = examples::extract(&html);
+    if found.is_empty() {
+        return Err(format!("no examples found on the day {day} puzzle page").into());
+    }
+    for path in examples::write_fixtures(Path::new(examples::FIXTURE_DIR), day, &found)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn render(
+    day: u8,
+    part: u8,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let contents = load_input(day, input.as_ref())?;
+    let (grid, highlighted) = scene(day, part, &contents)?;
+    let (grid, palette) = (grid.as_array(), Palette::default());
+    let Some(path) = output else {
+        print!("{}", render::ansi(grid, &highlighted, &palette));
+        return Ok(());
+    };
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => render::png(fs::File::create(&path)?, grid, &highlighted, &palette)?,
+        Some("svg") => fs::write(&path, render::svg(grid, &highlighted, &palette))?,
+        _ => return Err(format!("{} should end in .png or .svg", path.display()).into()),
+    }
+    println!("{}", path.display());
+    Ok(())
+}
+
+// The map a day works on and the cells a part picks out of it
+fn scene(
+    day: u8,
+    part: u8,
+    contents: &str,
+) -> std::result::Result<(Grid<char>, HashSet<CoOrd>), Box<dyn Error>> {
+    Ok(match (day, part) {
+        (6, 1) => {
+            let map = sixth::Day06::parse(contents)?;
+            let route = sixth::route(&map);
+            (map.into_grid(), route)
+        }
+        (6, _) => {
+            let map = sixth::Day06::parse(contents)?;
+            let obstacles = sixth::loop_obstacles(&map);
+            (map.into_grid(), obstacles)
+        }
+        (8, _) => {
+            let (grid, pairs) = eighth::Day08::parse(contents)?;
+            let antinodes = eighth::antinodes(&grid, &pairs, part == 1);
+            (grid, antinodes)
+        }
+        _ => return Err(format!("Day {day} has nothing to render").into()),
+    })
+}
+
+fn replay(
+    day: u8,
+    part: u8,
+    input: Option<PathBuf>,
+    output: PathBuf,
+    mut recorder: Recorder,
+) -> std::result::Result<(), Box<dyn Error>> {
+    if day != 6 {
+        return Err(format!("Day {day} has nothing to replay").into());
+    }
+    let map = sixth::Day06::parse(&load_input(day, input.as_ref())?)?;
+    let extra = if part == 2 {
+        let first = sixth::loop_obstacles(&map).into_iter().min();
+        Some(first.ok_or("no single obstacle traps the guard in a loop")?)
+    } else {
+        None
+    };
+    let walk = sixth::replay(&map, extra, &mut recorder);
+    if output.extension().is_some_and(|e| e == "gif") {
+        recorder.write_gif(fs::File::create(&output)?)?;
+        println!("{}", output.display());
+    } else {
+        for path in recorder.write_pngs(&output)? {
+            println!("{}", path.display());
+        }
+    }
+    if let Some(o) = extra {
+        println!(
+            "An obstacle at row {}, column {} {}",
+            o.i,
+            o.j,
+            if walk.looped {
+                "makes a loop"
+            } else {
+                "lets the guard out"
+            }
+        );
+    }
+    Ok(())
+}
+
+fn diagnose(
+    day: u8,
+    part: u8,
+    input: Option<PathBuf>,
+    dampener: Option<usize>,
+    csv: Option<PathBuf>,
+) -> std::result::Result<(), Box<dyn Error>> {
+    if day != 2 {
+        return Err(format!("Day {day} has nothing to diagnose").into());
+    }
+    let reports = second::Day02::parse(&load_input(day, input.as_ref())?)?;
+    let dampener = dampener.unwrap_or(usize::from(part == 2));
+    let verdicts = second::verdicts(&reports, &SafetyPolicy::default().with_dampener(dampener));
+    match csv {
+        Some(path) => {
+            second::write_csv(std::io::BufWriter::new(fs::File::create(&path)?), &verdicts)?;
+            println!("{}", path.display());
+        }
+        None => {
+            for verdict in &verdicts {
+                println!("{verdict}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn submit(day: u8, part: u8, answer: Option<String>) -> std::result::Result<(), Box<dyn Error>> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let d = registry::find(day).ok_or(format!("Day {day} has no registered solver"))?;
+            (d.parts[usize::from(part) - 1])(&load_input(day, None)?)?.to_string()
+        }
+    };
+    let client = Client::from_env(INPUT_DIR);
+    let submission = submit::submit(&client, day, part, &answer)?;
+    let source = if submission.cached {
+        " (from local record)"
+    } else {
+        ""
+    };
+    println!(
+        "Day {day} part {part}: {answer} is {}{source}",
+        submission.verdict
+    );
+    Ok(())
+}
+
+// The input as a reader, so days that can stream it never read it all in
+fn open_input(day: u8, input: Option<&PathBuf>) -> Result<BufReader<File>> {
+    let path = match input {
+        Some(path) => path.clone(),
+        None => Client::from_env(INPUT_DIR).input_file(day)?,
+    };
+    Ok(BufReader::new(File::open(path)?))
+}
+
+fn load_input(day: u8, input: Option<&PathBuf>) -> Result<String> {
+    match input {
+        Some(path) => utils::file_input(&path.to_string_lossy()),
+        None => Client::from_env(INPUT_DIR).input(day),
+    }
 }
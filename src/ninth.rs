@@ -1,134 +1,106 @@
-#[path = "utils.rs"]
-mod utils;
-use std::collections::HashSet;
-
-pub fn checksum(input: &str) -> usize {
-    let (big_string, check_size) = clean(input);
-    let backfills: Vec<String> = big_string
-        .clone()
-        .iter()
-        .cloned()
-        .filter(|s| *s != ".")
-        .rev()
-        .collect();
-    let mut backfill_index = 0;
-    let mut checksum: Vec<usize> = Vec::new();
-    for (i, _) in (0..check_size).enumerate() {
-        let existing = big_string.get(i).unwrap().parse();
-        match existing {
-            Ok(num) => checksum.push(num),
-            Err(_) => {
-                let backfill: usize = backfills.get(backfill_index).unwrap().parse().unwrap();
-                checksum.push(backfill);
-                backfill_index += 1;
-            }
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse, RangeSet};
+use std::ops::Range;
+
+pub struct Day09;
+
+// Where each file sits on the disk, indexed by file id. Free space is whatever
+// the files don't cover.
+pub struct Disk {
+    files: Vec<Range<usize>>,
+}
+
+impl Disk {
+    // One entry per block, holding the id of the file stored there
+    fn blocks(&self) -> Vec<Option<usize>> {
+        let size = self.files.last().map_or(0, |f| f.end);
+        let mut blocks = vec![None; size];
+        for (id, file) in self.files.iter().enumerate() {
+            blocks[file.clone()].fill(Some(id));
         }
+        blocks
     }
-    checksum
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (index, &value)| acc + index * value)
 }
 
-pub fn whole_files(input: &str) -> usize {
-    let (mut big_string, check_size) = clean(input);
-    let mut backfills: Vec<String> = big_string
-        .clone()
-        .iter()
-        .cloned()
-        .filter(|s| *s != ".")
-        .collect();
-    let distinct: HashSet<String> = backfills.iter().cloned().collect();
-    let mut distinct_ordered: Vec<String> = distinct.into_iter().collect();
-    distinct_ordered.sort_by(|a, b| b.cmp(a));
-    for fig in distinct_ordered {
-        let first_value_index = big_string.iter().position(|s| *s == fig);
-        match first_value_index {
-            Some(value_index) => {
-                let backfill: Vec<String> = backfills
-                    .iter()
-                    .filter(|&s| *s == fig)
-                    .map(|s| s.clone())
-                    .collect();
-                if backfill.len() > 0 {
-                    let first_fill_index = big_string
-                        .windows(backfill.len())
-                        .position(|window| window.iter().all(|s| s == "."));
-                    match first_fill_index {
-                        Some(fill_index) => {
-                            if fill_index < value_index {
-                                let matching_indices: Vec<usize> = big_string
-                                    .windows(backfill.len())
-                                    .enumerate()
-                                    .filter(|(i, window)| *window == backfill.as_slice())
-                                    .map(|(i, _)| i)
-                                    .collect();
-                                if matching_indices.len() == 1 {
-                                    big_string.splice(
-                                        fill_index..(fill_index + backfill.len()),
-                                        backfill.clone(),
-                                    );
-                                    let replacement = vec![String::from("."); backfill.len()];
-                                    big_string.splice(
-                                        matching_indices[0]..(matching_indices[0] + backfill.len()),
-                                        replacement,
-                                    );
-                                }
-                            }
-                        }
-                        None => {}
-                    }
-                }
-            }
-            None => {}
+impl Solution for Day09 {
+    type Input = Disk;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        clean(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(compact(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(defragment(input).into())
+    }
+}
+
+pub fn checksum(input: &str) -> Result<usize> {
+    Ok(compact(&clean(input)?))
+}
+
+pub fn whole_files(input: &str) -> Result<usize> {
+    Ok(defragment(&clean(input)?))
+}
+
+// Moves single blocks from the end of the disk into the leftmost free block
+fn compact(disk: &Disk) -> usize {
+    let mut blocks = disk.blocks();
+    let (mut front, mut back) = (0, blocks.len());
+    while front < back {
+        if blocks[front].is_some() {
+            front += 1;
+        } else if blocks[back - 1].is_none() {
+            back -= 1;
+        } else {
+            blocks.swap(front, back - 1);
         }
     }
-    big_string
+    blocks
         .iter()
         .enumerate()
-        .fold(0, |acc, (index, &ref value)| {
-            acc + safe_product(index, value.to_string())
-        })
+        .map(|(index, id)| id.map_or(0, |id| index * id))
+        .sum()
 }
 
-fn safe_product(index: usize, value: String) -> usize {
-    match value.parse::<usize>() {
-        Ok(i) => index * i,
-        Err(_) => 0,
+// Tries each file once, highest id first, moving it whole into the leftmost
+// gap before it that fits
+fn defragment(disk: &Disk) -> usize {
+    let mut files = disk.files.clone();
+    let mut occupied: RangeSet = files.iter().cloned().collect();
+    for file in files.iter_mut().rev() {
+        if let Some(gap) = occupied.first_gap(0..file.start, file.len()) {
+            let moved = gap.start..gap.start + file.len();
+            occupied.remove(file.clone());
+            occupied.insert(moved.clone());
+            *file = moved;
+        }
     }
+    files
+        .into_iter()
+        .enumerate()
+        .map(|(id, file)| id * file.sum::<usize>())
+        .sum()
 }
 
-fn clean(input: &str) -> (Vec<String>, usize) {
-    let mut big_string: Vec<String> = Vec::new();
-    let mut check_size = 0;
-    let mut fid = 0;
-
-    for (i, c) in input.trim().chars().enumerate() {
+fn clean(input: &str) -> Result<Disk> {
+    let disk_map = parse::lines(input)
+        .find(|line| !line.is_blank())
+        .ok_or_else(|| Error::InvalidInput("the disk map is empty".to_string()))?;
+    let mut files = Vec::new();
+    let mut position = 0;
+    for (i, length) in parse::digits(disk_map)?.into_iter().enumerate() {
+        let length = length as usize;
         if i % 2 == 0 {
-            let file = c.to_digit(10);
-            match file {
-                Some(num) => {
-                    for _ in 0..num {
-                        big_string.push(fid.to_string());
-                        check_size += 1;
-                    }
-                    fid += 1;
-                }
-                None => {}
-            }
-        } else {
-            let dig = c.to_digit(10);
-            match dig {
-                Some(num) => {
-                    for _ in 0..num {
-                        big_string.push(".".to_string());
-                    }
-                }
-                None => println!("Character {:?} at index {:?}", c, i),
-            }
+            files.push(position..position + length);
         }
+        position += length;
     }
-    (big_string, check_size)
+    Ok(Disk { files })
 }
 
 #[cfg(test)]
@@ -139,7 +111,7 @@ mod tests {
     fn test_checksum() {
         let input = r#"2333133121414131402"#;
 
-        let result = checksum(input);
+        let result = checksum(input).unwrap();
         assert_eq!(result, 1928);
     }
 
@@ -147,7 +119,7 @@ mod tests {
     fn test_whole_files() {
         let input = r#"2333133121414131402"#;
 
-        let result = whole_files(input);
+        let result = whole_files(input).unwrap();
         assert_eq!(result, 2858);
     }
 
@@ -155,15 +127,7 @@ mod tests {
     fn test_whole_files_again() {
         let input = r#"2333133121414131401"#;
 
-        let result = whole_files(input);
+        let result = whole_files(input).unwrap();
         assert_eq!(result, 2746);
     }
 }
-
-/*
-00...111...2...333.44.5555.6666.777.888899
-0099.111...2...333.44.5555.6666.777.8888..
-0099.1117772...333.44.5555.6666.....8888..
-0099.111777244.333....5555.6666.....8888..
-00992111777.44.333....5555.6666.....8888..
-*/
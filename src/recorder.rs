@@ -0,0 +1,208 @@
+use crate::error::Result;
+use crate::render::{self, Palette, Rgb};
+use crate::utils::{CoOrd, Vec2};
+use gif::{EncodingError, EncodingFormatError};
+use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// A snapshot of the grid and the cells highlighted in it
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub grid: Array2<char>,
+    pub highlighted: HashSet<CoOrd>,
+}
+
+// Collects frames as a simulation runs, to replay it afterwards as an animated
+// GIF or a numbered run of PNGs
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    frames: Vec<Frame>,
+    // Every cell passed to push_segment so far
+    trail: HashSet<CoOrd>,
+    pub fps: u16,
+    pub cell_size: u32,
+    pub palette: Palette,
+}
+
+impl Recorder {
+    pub fn new(fps: u16, cell_size: u32) -> Self {
+        Recorder {
+            frames: Vec::new(),
+            trail: HashSet::new(),
+            fps: fps.max(1),
+            cell_size: cell_size.max(1),
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn push(&mut self, grid: Array2<char>, highlighted: HashSet<CoOrd>) {
+        self.frames.push(Frame { grid, highlighted });
+    }
+
+    // Adds the straight (or diagonal) run of cells from `from` to `to` to the
+    // trail, then pushes `grid` with the whole trail so far highlighted
+    pub fn push_segment(&mut self, grid: Array2<char>, from: CoOrd, to: CoOrd) {
+        let step = to - from;
+        let length = step.i.abs().max(step.j.abs());
+        self.trail.extend((0..=length).filter_map(|n| {
+            from.checked_add(
+                Vec2::new(step.i.signum() * n, step.j.signum() * n),
+                grid.dim(),
+            )
+        }));
+        let highlighted = self.trail.clone();
+        self.push(grid, highlighted);
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // GIF delays are in hundredths of a second
+    fn delay(&self) -> u16 {
+        (100 / self.fps).max(1)
+    }
+
+    // Every frame shares one colour table, so there can be at most 256 colours
+    // across the whole recording. The picture is as big as the largest frame.
+    pub fn write_gif<W: Write>(&self, writer: W) -> Result<()> {
+        let mut table: HashMap<Rgb, u8> = HashMap::new();
+        let mut indexed = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let colours = render::colours(&frame.grid, &frame.highlighted, &self.palette);
+            let mut indices = Vec::with_capacity(colours.len());
+            for colour in colours.iter() {
+                let index = match table.get(colour) {
+                    Some(&index) => index,
+                    None => {
+                        let index = u8::try_from(table.len())
+                            .map_err(|_| EncodingError::from(EncodingFormatError::TooManyColors))?;
+                        table.insert(*colour, index);
+                        index
+                    }
+                };
+                indices.push(index);
+            }
+            let (height, width) = frame.grid.dim();
+            let indices =
+                Array2::from_shape_vec((height, width), indices).expect("one index per cell");
+            indexed.push(indices);
+        }
+
+        let size = |cells: usize| {
+            u16::try_from(cells as u32 * self.cell_size).map_err(|_| {
+                EncodingError::from(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "frames are too large for a GIF",
+                ))
+            })
+        };
+        let width = size(indexed.iter().map(|f| f.ncols()).max().unwrap_or(0))?;
+        let height = size(indexed.iter().map(|f| f.nrows()).max().unwrap_or(0))?;
+
+        let mut colour_table = vec![0; table.len() * 3];
+        for (Rgb(r, g, b), &index) in &table {
+            colour_table[usize::from(index) * 3..][..3].copy_from_slice(&[*r, *g, *b]);
+        }
+        let mut encoder = gif::Encoder::new(writer, width, height, &colour_table)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for indices in indexed {
+            let mut frame = gif::Frame::from_indexed_pixels(
+                size(indices.ncols())?,
+                size(indices.nrows())?,
+                render::scale(&indices, self.cell_size),
+                None,
+            );
+            frame.delay = self.delay();
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    // One PNG per frame in `dir`, numbered in order. The frame rate is up to
+    // whatever stitches them together.
+    pub fn write_pngs(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let digits = self.frames.len().to_string().len().max(4);
+        let mut written = Vec::with_capacity(self.frames.len());
+        for (n, frame) in self.frames.iter().enumerate() {
+            let path = dir.join(format!("frame{n:0digits$}.png"));
+            let (width, height, pixels) = render::pixels(
+                &frame.grid,
+                &frame.highlighted,
+                &self.palette,
+                self.cell_size,
+            );
+            render::write_png(File::create(&path)?, width, height, &pixels)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use tempfile::tempdir;
+
+    fn recording() -> Recorder {
+        let grid = array![['.', '.', '.'], ['.', '#', '.'], ['.', '.', '.']];
+        let mut recorder = Recorder::new(4, 2);
+        recorder.push(grid.clone(), HashSet::new());
+        recorder.push_segment(grid.clone(), CoOrd { i: 0, j: 0 }, CoOrd { i: 0, j: 2 });
+        recorder.push_segment(grid, CoOrd { i: 0, j: 2 }, CoOrd { i: 2, j: 2 });
+        recorder
+    }
+
+    #[test]
+    fn test_segments_build_a_trail() {
+        let recorder = recording();
+        assert_eq!(recorder.len(), 3);
+        assert!(recorder.frames()[0].highlighted.is_empty());
+        assert_eq!(recorder.frames()[1].highlighted.len(), 3);
+        assert_eq!(recorder.frames()[2].highlighted.len(), 5);
+        assert!(recorder.frames()[2]
+            .highlighted
+            .contains(&CoOrd { i: 1, j: 2 }));
+    }
+
+    #[test]
+    fn test_write_gif() {
+        let mut bytes = Vec::new();
+        recording().write_gif(&mut bytes).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(bytes.as_slice())
+            .unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (6, 6));
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 25);
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+    }
+
+    #[test]
+    fn test_write_pngs() {
+        let dir = tempdir().unwrap();
+        let written = recording().write_pngs(dir.path()).unwrap();
+        let names: Vec<_> = written
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["frame0000.png", "frame0001.png", "frame0002.png"]);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::bench::{bench_solution, Bench};
+use crate::error::Result;
+use crate::solution::{solve_part1, solve_part2, solve_reader, Answer, Outcome};
+use std::io::BufRead;
+
+pub type Solver = fn(&str) -> Result<Answer>;
+// Parses once from a reader and runs the given parts
+pub type ReaderSolver = fn(&mut dyn BufRead, &[u8]) -> Result<Vec<Outcome>>;
+
+pub struct Day {
+    pub number: u8,
+    pub name: &'static str,
+    pub parts: [Solver; 2],
+    pub solve: ReaderSolver,
+    pub bench: Bench,
+}
+
+macro_rules! day {
+    ($number:expr, $module:ident, $solution:ident) => {
+        Day {
+            number: $number,
+            name: stringify!($module),
+            parts: [
+                solve_part1::<crate::$module::$solution>,
+                solve_part2::<crate::$module::$solution>,
+            ],
+            solve: solve_reader::<crate::$module::$solution>,
+            bench: bench_solution::<crate::$module::$solution>,
+        }
+    };
+}
+
+// Every registered day, handed to `$with` as one day! per line. Expanding it
+// here builds DAYS, and tests/examples.rs expands it into a test per day. Kept
+// in day order so `--all` can simply walk DAYS. `aoc new-day` adds to this
+// list, so keep one day! per line.
+#[macro_export]
+macro_rules! registered_days {
+    ($with:ident) => {
+        $with! {
+            day!(1, first, Day01),
+            day!(2, second, Day02),
+            day!(3, third, Day03),
+            day!(4, fourth, Day04),
+            day!(5, fifth, Day05),
+            day!(6, sixth, Day06),
+            day!(7, seventh, Day07),
+            day!(8, eighth, Day08),
+            day!(9, ninth, Day09),
+        }
+    };
+}
+
+macro_rules! day_slice {
+    ($($day:expr),* $(,)?) => {
+        &[$($day),*]
+    };
+}
+
+pub const DAYS: &[Day] = registered_days!(day_slice);
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_in_order() {
+        let numbers: Vec<u8> = DAYS.iter().map(|d| d.number).collect();
+        assert_eq!(&numbers[..9], (1..=9).collect::<Vec<u8>>());
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(6).map(|d| d.name), Some("sixth"));
+        assert!(find(0).is_none());
+        assert!(find(26).is_none());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::error::Result;
+use crate::utils::CoOrd;
+use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+
+// Pixels along each side of a cell in PNG and SVG output
+pub const CELL_SIZE: u32 = 8;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// Glyphs without a colour of their own (antenna frequencies, say) are spread
+// over these so that neighbouring letters still look different
+const FALLBACK: [Rgb; 6] = [
+    Rgb(0xe0, 0x6c, 0x75),
+    Rgb(0x98, 0xc3, 0x79),
+    Rgb(0xe5, 0xc0, 0x7b),
+    Rgb(0x61, 0xaf, 0xef),
+    Rgb(0xc6, 0x78, 0xdd),
+    Rgb(0x56, 0xb6, 0xc2),
+];
+
+#[derive(Clone, Debug)]
+pub struct Palette {
+    glyphs: HashMap<char, Rgb>,
+    // Drawn behind highlighted cells, whatever their glyph
+    pub highlight: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            glyphs: HashMap::from([('.', Rgb(0x28, 0x2c, 0x34)), ('#', Rgb(0xab, 0xb2, 0xbf))]),
+            highlight: Rgb(0xff, 0xd7, 0x00),
+        }
+    }
+}
+
+impl Palette {
+    pub fn with(mut self, glyph: char, colour: Rgb) -> Self {
+        self.glyphs.insert(glyph, colour);
+        self
+    }
+
+    pub fn colour(&self, glyph: char) -> Rgb {
+        self.glyphs
+            .get(&glyph)
+            .copied()
+            .unwrap_or(FALLBACK[glyph as usize % FALLBACK.len()])
+    }
+
+    // The colour a cell is filled with in the image formats
+    fn fill(&self, glyph: char, highlighted: bool) -> Rgb {
+        if highlighted {
+            self.highlight
+        } else {
+            self.colour(glyph)
+        }
+    }
+}
+
+// The grid as 24-bit colour text, one line per row, with highlighted cells
+// drawn on the highlight colour
+pub fn ansi(grid: &Array2<char>, highlighted: &HashSet<CoOrd>, palette: &Palette) -> String {
+    let mut out = String::new();
+    for (i, row) in grid.rows().into_iter().enumerate() {
+        for (j, &glyph) in row.iter().enumerate() {
+            let Rgb(r, g, b) = palette.colour(glyph);
+            let _ = write!(out, "\x1b[38;2;{r};{g};{b}m");
+            if highlighted.contains(&CoOrd { i, j }) {
+                let Rgb(r, g, b) = palette.highlight;
+                let _ = write!(out, "\x1b[48;2;{r};{g};{b}m");
+            }
+            let _ = write!(out, "{glyph}\x1b[0m");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// One square per cell, with the background glyph's colour drawn once underneath
+pub fn svg(grid: &Array2<char>, highlighted: &HashSet<CoOrd>, palette: &Palette) -> String {
+    let (height, width) = grid.dim();
+    let background = palette.colour('.');
+    let mut out = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">
+<rect width="100%" height="100%" fill="{}"/>
+"#,
+        width as u32 * CELL_SIZE,
+        height as u32 * CELL_SIZE,
+        background.hex()
+    );
+    for ((i, j), &glyph) in grid.indexed_iter() {
+        let fill = palette.fill(glyph, highlighted.contains(&CoOrd { i, j }));
+        if fill != background {
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{CELL_SIZE}" height="{CELL_SIZE}" fill="{}"/>"#,
+                j as u32 * CELL_SIZE,
+                i as u32 * CELL_SIZE,
+                fill.hex()
+            );
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+pub fn png<W: Write>(
+    writer: W,
+    grid: &Array2<char>,
+    highlighted: &HashSet<CoOrd>,
+    palette: &Palette,
+) -> Result<()> {
+    let (width, height, pixels) = pixels(grid, highlighted, palette, CELL_SIZE);
+    write_png(writer, width, height, &pixels)
+}
+
+// Encodes RGB bytes as laid out by `pixels`
+pub fn write_png<W: Write>(writer: W, width: u32, height: u32, pixels: &[u8]) -> Result<()> {
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    Ok(())
+}
+
+// The colour each cell is drawn in, highlight included
+pub fn colours(
+    grid: &Array2<char>,
+    highlighted: &HashSet<CoOrd>,
+    palette: &Palette,
+) -> Array2<Rgb> {
+    Array2::from_shape_fn(grid.dim(), |(i, j)| {
+        palette.fill(grid[[i, j]], highlighted.contains(&CoOrd { i, j }))
+    })
+}
+
+// Every cell blown up to `cell_size` square, row by row
+pub fn scale<T: Copy>(cells: &Array2<T>, cell_size: u32) -> Vec<T> {
+    let scale = cell_size as usize;
+    let mut scaled = Vec::with_capacity(cells.len() * scale * scale);
+    for row in cells.rows() {
+        let line: Vec<T> = row
+            .iter()
+            .flat_map(|&cell| std::iter::repeat_n(cell, scale))
+            .collect();
+        for _ in 0..scale {
+            scaled.extend_from_slice(&line);
+        }
+    }
+    scaled
+}
+
+// The image's width and height, and its RGB bytes
+pub fn pixels(
+    grid: &Array2<char>,
+    highlighted: &HashSet<CoOrd>,
+    palette: &Palette,
+    cell_size: u32,
+) -> (u32, u32, Vec<u8>) {
+    let (height, width) = grid.dim();
+    let pixels = scale(&colours(grid, highlighted, palette), cell_size)
+        .into_iter()
+        .flat_map(|Rgb(r, g, b)| [r, g, b])
+        .collect();
+    (width as u32 * cell_size, height as u32 * cell_size, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn picture() -> (Array2<char>, HashSet<CoOrd>) {
+        let grid = array![['.', '#'], ['a', '.']];
+        (grid, HashSet::from([CoOrd { i: 1, j: 1 }]))
+    }
+
+    #[test]
+    fn test_ansi() {
+        let (grid, highlighted) = picture();
+        let palette = Palette::default().with('a', Rgb(1, 2, 3));
+        let out = ansi(&grid, &highlighted, &palette);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("\x1b[38;2;1;2;3ma\x1b[0m"));
+        assert!(lines[1].contains("\x1b[48;2;255;215;0m.\x1b[0m"));
+    }
+
+    #[test]
+    fn test_svg() {
+        let (grid, highlighted) = picture();
+        let out = svg(&grid, &highlighted, &Palette::default());
+        assert!(
+            out.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16""#)
+        );
+        // The wall, the letter and the highlight; the remaining floor is background
+        assert_eq!(out.matches("<rect x=").count(), 3);
+        assert!(out.contains(r##"<rect x="8" y="8" width="8" height="8" fill="#ffd700"/>"##));
+    }
+
+    #[test]
+    fn test_scale() {
+        let cells = array![[1, 2], [3, 4]];
+        assert_eq!(
+            scale(&cells, 2),
+            vec![1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]
+        );
+    }
+
+    #[test]
+    fn test_png() {
+        let (grid, highlighted) = picture();
+        let mut bytes = Vec::new();
+        png(&mut bytes, &grid, &highlighted, &Palette::default()).unwrap();
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).unwrap();
+        assert_eq!((info.width, info.height), (16, 16));
+        // Bottom right pixel is the highlighted cell
+        assert_eq!(&buffer[buffer.len() - 3..], &[0xff, 0xd7, 0x00]);
+    }
+}
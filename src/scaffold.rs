@@ -0,0 +1,160 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.template");
+
+// Zero-padded so new days sort in order alongside each other
+pub fn module_name(day: u8) -> Result<String> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::InvalidInput(format!("there is no day {day}")));
+    }
+    Ok(format!("day{day:02}"))
+}
+
+pub fn struct_name(day: u8) -> String {
+    format!("Day{day:02}")
+}
+
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("{{STRUCT}}", &struct_name(day))
+        .replace("{{NUMBER}}", &day.to_string())
+}
+
+// Writes src/<module>.rs, declares it in lib.rs, registers it (which also puts
+// it in the fixture tests) and leaves an empty input file to paste into.
+// Nothing is touched if the day already exists.
+pub fn create(root: &Path, day: u8, input_slot: &Path) -> Result<Vec<PathBuf>> {
+    let name = module_name(day)?;
+    let module_path = root.join("src").join(format!("{name}.rs"));
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+    if module_path.exists() {
+        return Err(Error::InvalidInput(format!(
+            "{} already exists",
+            module_path.display()
+        )));
+    }
+    let lib = declare_module(&fs::read_to_string(&lib_path)?, &name);
+    let registry = register_day(&fs::read_to_string(&registry_path)?, day, &name)?;
+
+    fs::write(&module_path, render(day))?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+    let mut written = vec![module_path, lib_path, registry_path];
+    if !input_slot.exists() {
+        if let Some(dir) = input_slot.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(input_slot, "")?;
+        written.push(input_slot.to_path_buf());
+    }
+    Ok(written)
+}
+
+// lib.rs is only `pub mod` lines, kept in alphabetical order
+fn declare_module(lib: &str, name: &str) -> String {
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+    let declaration = format!("pub mod {name};");
+    let position = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod ") && line.as_str() > declaration.as_str())
+        .unwrap_or(lines.len());
+    lines.insert(position, declaration);
+    lines.join("\n") + "\n"
+}
+
+// Adds a day! line to the registered days, keeping them in day order and
+// indented like the lines already there
+fn register_day(registry: &str, day: u8, name: &str) -> Result<String> {
+    let entry_rx = Regex::new(r"^(\s*)day!\((\d+),").unwrap();
+    let mut lines: Vec<String> = registry.lines().map(String::from).collect();
+    let (mut before_later, mut after_last) = (None, None);
+    let mut indent = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(c) = entry_rx.captures(line) {
+            indent = c[1].to_string();
+            let number: u8 = c[2].parse()?;
+            if number == day {
+                return Err(Error::InvalidInput(format!(
+                    "day {day} is already registered"
+                )));
+            }
+            if number > day && before_later.is_none() {
+                before_later = Some(i);
+            }
+            after_last = Some(i + 1);
+        }
+    }
+    let position = before_later.or(after_last).ok_or_else(|| {
+        Error::InvalidInput("could not find the day list in registry.rs".to_string())
+    })?;
+    lines.insert(
+        position,
+        format!("{indent}day!({day}, {name}, {}),", struct_name(day)),
+    );
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const LIB: &str = "pub mod sixth;\npub mod solution;\npub mod third;\n";
+    const REGISTRY: &str = r#"macro_rules! registered_days {
+    ($with:ident) => {
+        $with! {
+            day!(3, third, Day03),
+            day!(6, sixth, Day06),
+        }
+    };
+}
+"#;
+
+    #[test]
+    fn test_module_name() {
+        assert_eq!(module_name(1).unwrap(), "day01");
+        assert_eq!(module_name(10).unwrap(), "day10");
+        assert_eq!(module_name(25).unwrap(), "day25");
+        assert!(module_name(0).is_err());
+        assert!(module_name(26).is_err());
+    }
+
+    #[test]
+    fn test_create() {
+        let root = tempdir().unwrap();
+        let src = root.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), LIB).unwrap();
+        fs::write(src.join("registry.rs"), REGISTRY).unwrap();
+        let slot = root.path().join("inputs").join("day10.txt");
+
+        create(root.path(), 10, &slot).unwrap();
+        let module = fs::read_to_string(src.join("day10.rs")).unwrap();
+        assert!(module.contains("impl Solution for Day10"));
+        assert!(module.contains("Error::Unsolved(10, 2)"));
+        let lib = fs::read_to_string(src.join("lib.rs")).unwrap();
+        assert_eq!(
+            lib,
+            "pub mod day10;\npub mod sixth;\npub mod solution;\npub mod third;\n"
+        );
+        let registry = fs::read_to_string(src.join("registry.rs")).unwrap();
+        assert!(registry.contains(
+            "            day!(6, sixth, Day06),\n            day!(10, day10, Day10),\n        }"
+        ));
+
+        let slot = root.path().join("inputs").join("day04.txt");
+        create(root.path(), 4, &slot).unwrap();
+        let registry = fs::read_to_string(src.join("registry.rs")).unwrap();
+        assert!(registry
+            .contains("            day!(3, third, Day03),\n            day!(4, day04, Day04),\n"));
+        assert_eq!(fs::read_to_string(&slot).unwrap(), "");
+
+        assert!(create(root.path(), 10, &slot).is_err());
+        assert!(create(root.path(), 6, &slot).is_err());
+        assert!(!src.join("day06.rs").exists());
+    }
+}
@@ -1,81 +1,295 @@
-#[path = "utils.rs"]
-mod utils;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::utils::parse::{self, Line};
+use std::fmt;
+use std::io::Write;
 
-pub fn check_safety(input: &str) -> i32 {
-    let cleaned: Vec<Vec<i32>> = utils::string_to_2d_array(input, manipulate_input);
-    cleaned.iter().fold(0, |acc, x| acc + x[0])
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::lines(input)
+            .map(|line| match manipulate_input(line)? {
+                report if report.is_empty() => Err(Error::InvalidInput(format!(
+                    "line {} is an empty report",
+                    line.number
+                ))),
+                report => Ok(report),
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_safe(input, &SafetyPolicy::default()).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_safe(input, &SafetyPolicy::default().with_dampener(1)).into())
+    }
 }
 
-pub fn check_safety_dampened(input: &str) -> i32 {
-    let cleaned: Vec<Vec<i32>> = utils::string_to_2d_array(input, safety_dampener);
-    cleaned.iter().fold(0, |acc, x| acc + x[0])
+pub fn check_safety(input: &str) -> Result<usize> {
+    Ok(count_safe(&Day02::parse(input)?, &SafetyPolicy::default()))
 }
 
-fn manipulate_input(original: &str) -> Vec<i32> {
-    let parsed: Vec<i32> = original
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
-    let diffs = report_diffs(&parsed);
-    is_safe(&diffs)
+pub fn check_safety_dampened(input: &str) -> Result<usize> {
+    Ok(count_safe(
+        &Day02::parse(input)?,
+        &SafetyPolicy::default().with_dampener(1),
+    ))
 }
 
-fn safety_dampener(original: &str) -> Vec<i32> {
-    let parsed: Vec<i32> = original
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
-    can_be_safe(&parsed)
+pub fn count_safe(reports: &[Vec<i32>], policy: &SafetyPolicy) -> usize {
+    reports.iter().filter(|r| policy.is_safe(r)).count()
 }
 
-fn report_diffs(report: &Vec<i32>) -> Vec<i32> {
-    let mut diffs = Vec::with_capacity(report.len() - 1);
+fn manipulate_input(original: Line) -> Result<Vec<i32>> {
+    parse::words(original)
+}
 
-    for window in report.windows(2) {
-        diffs.push(window[1] - window[0]);
-    }
-    diffs
+// Which way the levels in a report have to move
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+    Either,
 }
 
-fn can_be_safe(report: &Vec<i32>) -> Vec<i32> {
-    if is_safe(&report_diffs(report)) == vec![1] {
-        return vec![1];
-    }
+// What makes a report safe: every step between neighbouring levels is between
+// min_step and max_step in size, all in the same direction, after removing at
+// most `dampener` levels. The default is part 1's rules.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SafetyPolicy {
+    pub min_step: i32,
+    pub max_step: i32,
+    pub trend: Trend,
+    pub dampener: usize,
+}
 
-    let sub_vectors: Vec<Vec<i32>> = (0..report.len())
-        .map(|i| {
-            let mut sub_vec = report.clone();
-            sub_vec.remove(i);
-            report_diffs(&sub_vec)
-        })
-        .collect();
-    for vector in sub_vectors {
-        if is_safe(&vector) == vec![1] {
-            return vec![1];
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy {
+            min_step: 1,
+            max_step: 3,
+            trend: Trend::Either,
+            dampener: 0,
         }
     }
-    vec![0]
 }
 
-fn is_safe(report: &Vec<i32>) -> Vec<i32> {
-    let safe = report.iter().all(|&x| x.signum() == report[0].signum())
-        && report
+impl SafetyPolicy {
+    pub fn with_dampener(self, dampener: usize) -> Self {
+        SafetyPolicy { dampener, ..self }
+    }
+
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        self.removals(report).is_some()
+    }
+
+    // The fewest levels that have to go for the report to be safe, or None if
+    // that's more than the dampener allows. A single level has no steps to get
+    // wrong, so it's safe.
+    pub fn removals(&self, report: &[i32]) -> Option<usize> {
+        self.removed_levels(report).map(|removed| removed.len())
+    }
+
+    // Which levels (0-based) the dampener takes out to make the report safe,
+    // fewest first
+    pub fn removed_levels(&self, report: &[i32]) -> Option<Vec<usize>> {
+        let directions: &[i32] = match self.trend {
+            Trend::Increasing => &[1],
+            Trend::Decreasing => &[-1],
+            Trend::Either => &[1, -1],
+        };
+        directions
             .iter()
-            .map(|&x| x.abs())
-            .min()
-            .map_or(false, |min| min >= 1)
-        && report
+            .filter_map(|&sign| self.removed_towards(report, sign))
+            .min_by_key(Vec::len)
+    }
+
+    // Keeping level i straight after level j means removing everything between
+    // them, so a level only has to look back dampener + 1 places to find the
+    // cheapest way to keep it. That makes this O(n * k) rather than retrying
+    // every combination of removals.
+    fn removed_towards(&self, report: &[i32], sign: i32) -> Option<Vec<usize>> {
+        let n = report.len();
+        // There are never more than n levels to remove, and capping k there
+        // keeps k + 1 from overflowing for a dampener of usize::MAX
+        let k = self.dampener.min(n);
+        // fewest[i]: if i is kept, the removals before it to keep everything so
+        // far safe and the level kept before it
+        let mut fewest: Vec<Option<(usize, Option<usize>)>> = Vec::with_capacity(n);
+        for i in 0..n {
+            let after = (i.saturating_sub(k + 1)..i).filter_map(|j| {
+                let step = (report[i] - report[j]) * sign;
+                let removed = fewest[j]?.0 + (i - j - 1);
+                (self.min_step..=self.max_step)
+                    .contains(&step)
+                    .then_some((removed, Some(j)))
+            });
+            // ...or everything before it goes and the report starts here
+            let best = after
+                .chain(std::iter::once((i, None)))
+                .min_by_key(|&(removed, _)| removed)
+                .filter(|&(removed, _)| removed <= k);
+            fewest.push(best);
+        }
+        // The last level kept can be followed by levels that are all removed
+        let (last, _) = fewest
             .iter()
-            .map(|&x| x.abs())
-            .max()
-            .map_or(false, |max| max <= 3);
-    if safe {
-        return vec![1];
-    } else {
-        vec![0]
+            .enumerate()
+            .filter_map(|(i, best)| best.map(|(removed, _)| (i, removed + (n - 1 - i))))
+            .filter(|&(_, removed)| removed <= k)
+            .min_by_key(|&(_, removed)| removed)?;
+        let mut kept = vec![false; n];
+        let mut at = Some(last);
+        while let Some(i) = at {
+            kept[i] = true;
+            at = fewest[i].and_then(|(_, before)| before);
+        }
+        Some((0..n).filter(|&i| !kept[i]).collect())
+    }
+
+    // The first step that breaks the rules with nothing removed. With either
+    // trend allowed, the first step that moves at all sets the direction.
+    pub fn first_violation(&self, report: &[i32]) -> Option<Violation> {
+        let mut direction = match self.trend {
+            Trend::Increasing => Some(1),
+            Trend::Decreasing => Some(-1),
+            Trend::Either => None,
+        };
+        for (i, pair) in report.windows(2).enumerate() {
+            let step = pair[1] - pair[0];
+            let reason = if step.abs() < self.min_step {
+                Some(Reason::StepTooSmall)
+            } else if step != 0 && direction.is_some_and(|d| step.signum() != d) {
+                Some(Reason::DirectionFlip)
+            } else if step.abs() > self.max_step {
+                Some(Reason::StepTooLarge)
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Some(Violation {
+                    level: i + 1,
+                    reason,
+                });
+            }
+            if step != 0 {
+                direction.get_or_insert(step.signum());
+            }
+        }
+        None
+    }
+
+    pub fn verdict(&self, line: usize, report: &[i32]) -> ReportVerdict {
+        ReportVerdict {
+            line,
+            violation: self.first_violation(report),
+            removed: self.removed_levels(report),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    DirectionFlip,
+    StepTooSmall,
+    StepTooLarge,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::DirectionFlip => write!(f, "direction flip"),
+            Reason::StepTooSmall => write!(f, "step too small"),
+            Reason::StepTooLarge => write!(f, "step too large"),
+        }
     }
 }
 
+// A bad step, by the (0-based) level it steps onto
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub level: usize,
+    pub reason: Reason,
+}
+
+// Why a report is or isn't safe. Lines and levels are 0-based here but counted
+// from 1 when printed, as they would be read off the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportVerdict {
+    pub line: usize,
+    pub violation: Option<Violation>,
+    // The levels the dampener removed, or None if it couldn't make the report safe
+    pub removed: Option<Vec<usize>>,
+}
+
+impl ReportVerdict {
+    pub fn is_safe(&self) -> bool {
+        self.removed.is_some()
+    }
+
+    const CSV_HEADER: &'static str = "line,safe,level,reason,removed";
+
+    // Removed levels are space separated within their field
+    fn csv_row(&self) -> String {
+        let (level, reason) = match self.violation {
+            Some(v) => ((v.level + 1).to_string(), v.reason.to_string()),
+            None => (String::new(), String::new()),
+        };
+        format!(
+            "{},{},{level},{reason},{}",
+            self.line + 1,
+            self.is_safe(),
+            levels(self.removed.as_deref().unwrap_or_default(), " ")
+        )
+    }
+}
+
+impl fmt::Display for ReportVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line + 1)?;
+        let broken = self
+            .violation
+            .map(|v| format!("{} at level {}", v.reason, v.level + 1));
+        match (&self.removed, broken) {
+            (Some(removed), Some(broken)) => {
+                write!(f, "safe without level {} ({broken})", levels(removed, ", "))
+            }
+            (Some(_), None) => write!(f, "safe"),
+            (None, Some(broken)) => write!(f, "unsafe, {broken}"),
+            (None, None) => write!(f, "unsafe"),
+        }
+    }
+}
+
+fn levels(levels: &[usize], separator: &str) -> String {
+    levels
+        .iter()
+        .map(|level| (level + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+pub fn verdicts(reports: &[Vec<i32>], policy: &SafetyPolicy) -> Vec<ReportVerdict> {
+    reports
+        .iter()
+        .enumerate()
+        .map(|(line, report)| policy.verdict(line, report))
+        .collect()
+}
+
+pub fn write_csv<W: Write>(mut writer: W, verdicts: &[ReportVerdict]) -> Result<()> {
+    writeln!(writer, "{}", ReportVerdict::CSV_HEADER)?;
+    for verdict in verdicts {
+        writeln!(writer, "{}", verdict.csv_row())?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,7 +303,7 @@ mod tests {
 8 6 4 4 1
 1 3 6 7 9"#;
 
-        let result = check_safety(input);
+        let result = check_safety(input).unwrap();
         assert_eq!(result, 2);
     }
 
@@ -102,7 +316,123 @@ mod tests {
 8 6 4 4 1
 1 3 6 7 9"#;
 
-        let result = check_safety_dampened(input);
+        let result = check_safety_dampened(input).unwrap();
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_safety_policy() {
+        let part1 = SafetyPolicy::default();
+        assert!(part1.is_safe(&[7, 6, 4, 2, 1]));
+        assert!(!part1.is_safe(&[1, 3, 2, 4, 5]));
+        assert!(part1.is_safe(&[4]));
+
+        let rising = SafetyPolicy {
+            trend: Trend::Increasing,
+            ..part1
+        };
+        assert!(!rising.is_safe(&[7, 6, 4, 2, 1]));
+        assert!(rising.is_safe(&[1, 3, 6, 7, 9]));
+
+        let big_steps = SafetyPolicy {
+            min_step: 2,
+            max_step: 5,
+            ..part1
+        };
+        assert!(big_steps.is_safe(&[1, 6, 8, 12]));
+        assert!(!big_steps.is_safe(&[1, 3, 4]));
+    }
+
+    #[test]
+    fn test_dampener() {
+        let policy = SafetyPolicy::default();
+        // The bad level can be first, in the middle, or last
+        assert_eq!(policy.with_dampener(1).removals(&[9, 1, 2, 3]), Some(1));
+        assert_eq!(policy.with_dampener(1).removals(&[1, 3, 2, 4, 5]), Some(1));
+        assert_eq!(policy.with_dampener(1).removals(&[1, 2, 3, 9]), Some(1));
+        assert_eq!(policy.with_dampener(1).removals(&[1, 2, 3]), Some(0));
+
+        let spiky = [1, 9, 2, 8, 3, 4];
+        assert_eq!(policy.with_dampener(1).removals(&spiky), None);
+        assert_eq!(policy.with_dampener(2).removals(&spiky), Some(2));
+        // Removing everything but one level always works
+        assert_eq!(policy.with_dampener(5).removals(&[9, 1, 9, 1]), Some(3));
+        assert_eq!(policy.with_dampener(3).removals(&[5, 5, 5, 5]), Some(3));
+        let unlimited = policy.with_dampener(usize::MAX);
+        assert_eq!(unlimited.removals(&[9, 1, 9, 1]), Some(3));
+        assert_eq!(unlimited.removals(&spiky), Some(2));
+    }
+
+    #[test]
+    fn test_removed_levels() {
+        let policy = SafetyPolicy::default().with_dampener(2);
+        assert_eq!(policy.removed_levels(&[1, 2, 3]), Some(vec![]));
+        assert_eq!(policy.removed_levels(&[1, 2, 9, 3]), Some(vec![2]));
+        assert_eq!(policy.removed_levels(&[1, 9, 2, 8, 3, 4]), Some(vec![1, 3]));
+        assert_eq!(policy.removed_levels(&[1, 9, 2, 8, 3, 7]), None);
+    }
+
+    #[test]
+    fn test_first_violation() {
+        let policy = SafetyPolicy::default();
+        let violation =
+            |report: &[i32]| policy.first_violation(report).map(|v| (v.level, v.reason));
+        assert_eq!(violation(&[7, 6, 4, 2, 1]), None);
+        assert_eq!(violation(&[1, 2, 7, 8, 9]), Some((2, Reason::StepTooLarge)));
+        assert_eq!(
+            violation(&[1, 3, 2, 4, 5]),
+            Some((2, Reason::DirectionFlip))
+        );
+        assert_eq!(violation(&[8, 6, 4, 4, 1]), Some((3, Reason::StepTooSmall)));
+        let rising = SafetyPolicy {
+            trend: Trend::Increasing,
+            ..policy
+        };
+        assert_eq!(
+            rising.first_violation(&[3, 2, 1]).map(|v| v.reason),
+            Some(Reason::DirectionFlip)
+        );
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let reports = Day02::parse("7 6 4 2 1\n1 2 7 8 9\n1 3 2 4 5\n9 7 6 2 1 8").unwrap();
+        let dampened = verdicts(&reports, &SafetyPolicy::default().with_dampener(1));
+        let lines: Vec<String> = dampened.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "line 1: safe",
+                "line 2: unsafe, step too large at level 3",
+                "line 3: safe without level 3 (direction flip at level 3)",
+                "line 4: unsafe, step too large at level 4",
+            ]
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &dampened).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "line,safe,level,reason,removed\n\
+             1,true,,,\n\
+             2,false,3,step too large,\n\
+             3,true,3,direction flip,3\n\
+             4,false,4,step too large,\n"
+        );
+    }
+
+    #[test]
+    fn test_malformed_report() {
+        let input = "7 6 4 2 1\n1 2 x 8 9";
+
+        let result = check_safety(input);
+        assert!(matches!(
+            result,
+            Err(Error::Syntax {
+                line: 2,
+                column: 5,
+                ..
+            })
+        ));
+    }
 }
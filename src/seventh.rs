@@ -1,25 +1,42 @@
-#[path = "utils.rs"]
-mod utils;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::utils::parse::{self, Line};
+use log::trace;
 use std::collections::HashSet;
 
-pub fn add_mult(input: &str) -> u128 {
-    let parsed = utils::string_to_2d_array(input, result_then_inputs);
-    let structured: Vec<(u128, Vec<u128>)> = parsed.iter().map(|v| prepare(v)).collect();
-    let results: Vec<u128> = structured
-        .iter()
-        .map(|(r, i)| do_the_math(r, i, false))
-        .collect();
-    results.iter().sum()
+pub struct Day07;
+
+impl Solution for Day07 {
+    // Each equation as its expected result followed by its operands
+    type Input = Vec<(u128, Vec<u128>)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::lines(input).map(result_then_inputs).collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(calibration_total(input, false).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(calibration_total(input, true).into())
+    }
 }
 
-pub fn concat_add_mult(input: &str) -> u128 {
-    let parsed = utils::string_to_2d_array(input, result_then_inputs);
-    let structured: Vec<(u128, Vec<u128>)> = parsed.iter().map(|v| prepare(v)).collect();
+pub fn add_mult(input: &str) -> Result<u128> {
+    Ok(calibration_total(&Day07::parse(input)?, false))
+}
+
+pub fn concat_add_mult(input: &str) -> Result<u128> {
+    Ok(calibration_total(&Day07::parse(input)?, true))
+}
+
+fn calibration_total(structured: &[(u128, Vec<u128>)], allow_concats: bool) -> u128 {
     let results: Vec<u128> = structured
         .iter()
-        .map(|(r, i)| do_the_math(r, i, true))
+        .map(|(r, i)| do_the_math(r, i, allow_concats))
         .collect();
-    println!("{:?}", results);
+    trace!("Results: {:?}", results);
     results.iter().sum()
 }
 
@@ -40,7 +57,7 @@ fn do_the_math(target: &u128, numbers: &[u128], allow_concats: bool) -> u128 {
         states = next_states;
     }
 
-    if states.contains(&target) {
+    if states.contains(target) {
         *target
     } else {
         0
@@ -52,28 +69,16 @@ fn concatenate(a: u128, b: u128) -> u128 {
     concatenated.parse::<u128>().unwrap()
 }
 
-fn prepare(input: &Vec<u128>) -> (u128, Vec<u128>) {
-    (*input.first().unwrap(), input[1..].to_vec())
-}
-
-fn result_then_inputs(string: &str) -> Vec<u128> {
-    let mut collected: Vec<u128> = Vec::new();
-    let mut line = string.split(":");
-    let expected = line
-        .next()
-        .and_then(|s| s.parse::<u128>().ok())
-        .unwrap_or(0);
-    collected.push(expected);
-    let inputs = line
-        .next()
-        .map(|s| {
-            s.split_whitespace()
-                .filter_map(|num| num.parse::<u128>().ok())
-                .collect::<Vec<u128>>()
-        })
-        .unwrap_or_else(Vec::new);
-    collected.extend(inputs);
-    collected
+// do_the_math relies on there being at least one operand
+fn result_then_inputs(line: Line) -> Result<(u128, Vec<u128>)> {
+    let (expected, inputs) = parse::key_values(line, ':')?;
+    if inputs.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "equation {:?} has no operands",
+            line.text
+        )));
+    }
+    Ok((expected, inputs))
 }
 
 #[cfg(test)]
@@ -92,7 +97,7 @@ mod tests {
 21037: 9 7 18 13
 292: 11 6 16 20"#;
 
-        let result = add_mult(input);
+        let result = add_mult(input).unwrap();
         assert_eq!(result, 3749);
     }
 
@@ -108,7 +113,7 @@ mod tests {
 21037: 9 7 18 13
 292: 11 6 16 20"#;
 
-        let result = concat_add_mult(input);
+        let result = concat_add_mult(input).unwrap();
         assert_eq!(result, 11387);
     }
 }
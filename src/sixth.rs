@@ -1,222 +1,264 @@
-#[path = "utils.rs"]
-mod utils;
-use ndarray::Array2;
+use crate::error::{Error, Result};
+use crate::recorder::Recorder;
+use crate::solution::{Answer, Solution};
+use crate::utils::cycle;
+use crate::utils::{CoOrd, Direction, Grid};
+use log::debug;
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Clone, Debug, PartialEq)]
-enum Direction {
-    Up,    // (-1, 0)
-    Right, // (0, 1)
-    Down,  // (-1, 0)
-    Left,  // (0, -1)
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = GuardMap;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let grid = Grid::parse(input)?;
+        match find_guard(&grid) {
+            Some(guard) => Ok(GuardMap { grid, guard }),
+            None => Err(Error::InvalidInput(
+                "there is no guard on the map".to_string(),
+            )),
+        }
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_visited(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_cycles(input).into())
+    }
 }
 
-pub fn guard_path(input: &str) -> u32 {
-    let mut grid = utils::vec_to_array2(utils::string_to_2d_array(input, utils::just_chars));
-    let (visited, _) = find_path(&mut grid, None);
-    (visited.len()) as u32
+pub fn guard_path(input: &str) -> Result<u32> {
+    Ok(count_visited(&Day06::parse(input)?))
 }
 
-pub fn cycles(input: &str) -> u32 {
-    let mut grid = utils::vec_to_array2(utils::string_to_2d_array(input, utils::just_chars));
-    let clean_grid = utils::vec_to_array2(utils::string_to_2d_array(input, utils::just_chars));
-    let (visited, _) = find_path(&mut grid, None);
-    let mut cycle_coords: HashSet<utils::CoOrd> = HashSet::new();
-    for location in visited {
-        let mut new_grid = clean_grid.clone();
-        let (_, c) = find_path(&mut new_grid, Some(location));
-        if c {
-            cycle_coords.insert(location);
-        }
+pub fn cycles(input: &str) -> Result<u32> {
+    Ok(count_cycles(&Day06::parse(input)?))
+}
+
+// Where the guard is and which way they're facing
+type Guard = (CoOrd, Direction);
+
+// The lab's map along with the guard found on it. Only parse makes these, so
+// there's always a guard to start from.
+#[derive(Clone, Debug)]
+pub struct GuardMap {
+    grid: Grid<char>,
+    guard: Guard,
+}
+
+impl GuardMap {
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
     }
-    for c in &cycle_coords {
-        println!("{:?}", c);
+
+    pub fn into_grid(self) -> Grid<char> {
+        self.grid
     }
-    cycle_coords.len() as u32
 }
 
-fn find_path(
-    grid: &mut Array2<char>,
-    new_obstacle: Option<utils::CoOrd>,
-) -> (HashSet<utils::CoOrd>, bool) {
-    let mut visited: HashSet<utils::CoOrd> = HashSet::new();
-    let mut directions = vec![
-        Direction::Up,
-        Direction::Right,
-        Direction::Down,
-        Direction::Left,
-    ]
-    .into_iter()
-    .cycle();
-    let mut cursor = find_guard(&grid);
-    visited.insert(cursor);
-    match new_obstacle {
-        Some(coord) => grid[[coord.i, coord.j]] = '#',
-        None => {}
+// The point here is to avoid hitting all the squares
+// We know where the guard is and whether it's travelling up, down, left or right
+// Since we know where the obstables are we can jump straight to the next one,
+// turn, and repeat. The guard's state at each turn is all the simulation needs,
+// so a loop is just that state coming round again.
+struct Lab {
+    size: (usize, usize),
+    obstacles_by_row: HashMap<usize, Vec<CoOrd>>,
+    obstacles_by_column: HashMap<usize, Vec<CoOrd>>,
+    // Part 2's one extra obstacle, kept apart so the lab needn't be rebuilt for it
+    extra: Option<CoOrd>,
+}
+
+impl Lab {
+    fn new(grid: &Grid<char>) -> Self {
+        let (obstacles_by_row, obstacles_by_column) = grid.find_all_by_line(&'#');
+        Lab {
+            size: grid.size(),
+            obstacles_by_row,
+            obstacles_by_column,
+            extra: None,
+        }
+    }
+
+    // The nearest obstacle in the guard's row or column that's in front of them
+    fn obstacle_ahead(&self, (at, facing): Guard) -> Option<CoOrd> {
+        let lane = match facing {
+            Direction::Up | Direction::Down => self.obstacles_by_column.get(&at.j),
+            _ => self.obstacles_by_row.get(&at.i),
+        };
+        let step = facing.delta();
+        lane.into_iter()
+            .flatten()
+            .chain(self.extra.iter())
+            .filter(|&&o| {
+                let gap = o - at;
+                // Ahead means a whole number of steps forward along the heading
+                (gap.i * step.j == gap.j * step.i) && gap.i * step.i + gap.j * step.j > 0
+            })
+            .min_by_key(|&&o| (o - at).manhattan())
+            .copied()
+    }
+
+    // The guard after walking up to the next obstacle and turning right, or None
+    // once they'd walk out of the lab
+    fn next_turn(&self, guard: &Guard) -> Option<Guard> {
+        let (_, facing) = *guard;
+        let obstacle = self.obstacle_ahead(*guard)?;
+        let stop = obstacle.checked_add(-facing.delta(), self.size)?;
+        Some((stop, facing.turn_right()))
     }
-    let (obstacles_by_row, obstacles_by_column) = utils::locate_all_in_grid(&grid, &'#');
-    // The point here is to avoid hitting all the squares
-    // We know where the guard is and whether it's travelling up, down, left or right
-    // Since we know where the obstables are we can add any squares not already
-    // in the visited set between the guard and the next obstacle immediately,
-    // and then change direction and repeat.
-    let mut ended = false;
-    while !ended {
-        let (newly_visited, termination, new_cursor) = walk(
-            grid,
-            &cursor,
-            &directions.next().unwrap(),
-            &obstacles_by_row,
-            &obstacles_by_column,
-        );
 
-        if termination == '!' {
-            visited.extend(newly_visited);
-            ended = true;
-        } else if termination == 'O' {
-            return (visited, true);
-        } else {
-            // cursor is now the last co-ord visited in the last run
-            // no need to update direction as our cycle will do that
-            cursor = new_cursor;
-            // update our visited set
-            visited.extend(newly_visited);
+    // Where the guard leaves the lab walking straight on from `at`
+    fn exit(&self, (at, facing): Guard) -> CoOrd {
+        let (height, width) = self.size;
+        match facing {
+            Direction::Up => CoOrd { i: 0, j: at.j },
+            Direction::Down => CoOrd {
+                i: height - 1,
+                j: at.j,
+            },
+            Direction::Left => CoOrd { i: at.i, j: 0 },
+            _ => CoOrd {
+                i: at.i,
+                j: width - 1,
+            },
         }
     }
-    (visited, false)
 }
 
-fn walk(
-    grid: &mut Array2<char>,
-    cursor: &utils::CoOrd,
-    direction: &Direction,
-    obstacles_by_row: &HashMap<usize, Vec<utils::CoOrd>>,
-    obstacles_by_column: &HashMap<usize, Vec<utils::CoOrd>>,
-) -> (Vec<utils::CoOrd>, char, utils::CoOrd) {
-    let (height, width) = (grid.shape()[0], grid.shape()[1]);
-    let default_vec: Vec<utils::CoOrd> = Vec::new();
-    if direction == &Direction::Up {
-        let obstacles = obstacles_by_column.get(&cursor.j).unwrap_or(&default_vec);
-        let max_less_than_i = obstacles.iter().filter(|&x| x.i < cursor.i).max();
-
-        match max_less_than_i {
-            Some(value) => {
-                let new_cursor = utils::CoOrd {
-                    i: value.i + 1,
-                    j: value.j,
-                };
-                if grid[[new_cursor.i, new_cursor.j]] == '⌜' {
-                    return (points_between(cursor, &new_cursor), 'O', new_cursor);
-                } else {
-                    grid[[new_cursor.i, new_cursor.j]] = '⌜';
-                }
-                return (points_between(cursor, &new_cursor), '#', new_cursor);
-            }
-            None => {
-                return (
-                    points_between(cursor, &utils::CoOrd { i: 0, j: cursor.j }),
-                    '!',
-                    *cursor,
-                )
-            }
-        }
-    } else if direction == &Direction::Down {
-        let obstacles = obstacles_by_column.get(&cursor.j).unwrap_or(&default_vec);
-        let min_greater_than_i = obstacles.iter().filter(|&x| x.i > cursor.i).min();
-
-        match min_greater_than_i {
-            Some(value) => {
-                let new_cursor = utils::CoOrd {
-                    i: value.i - 1,
-                    j: value.j,
-                };
-                if grid[[new_cursor.i, new_cursor.j]] == '⌟' {
-                    return (points_between(cursor, &new_cursor), 'O', new_cursor);
-                } else {
-                    grid[[new_cursor.i, new_cursor.j]] = '⌟';
-                }
-                return (points_between(cursor, &new_cursor), '#', new_cursor);
-            }
-            None => {
-                return (
-                    points_between(
-                        cursor,
-                        &utils::CoOrd {
-                            i: height - 1,
-                            j: cursor.j,
-                        },
-                    ),
-                    '!',
-                    *cursor,
-                )
-            }
+fn count_visited(map: &GuardMap) -> u32 {
+    route(map).len() as u32
+}
+
+fn count_cycles(map: &GuardMap) -> u32 {
+    loop_obstacles(map).len() as u32
+}
+
+// Every square the guard walks through
+pub fn route(map: &GuardMap) -> HashSet<CoOrd> {
+    visited(&Lab::new(&map.grid), map.guard)
+}
+
+// Where a single extra obstacle would trap the guard in a loop. Only a square
+// the guard would otherwise walk through can change their route.
+pub fn loop_obstacles(map: &GuardMap) -> HashSet<CoOrd> {
+    let guard = map.guard;
+    let mut lab = Lab::new(&map.grid);
+    let mut cycle_coords: HashSet<CoOrd> = HashSet::new();
+    for location in visited(&lab, guard) {
+        if location == guard.0 {
+            continue;
         }
-    } else if direction == &Direction::Left {
-        let obstacles = obstacles_by_row.get(&cursor.i).unwrap_or(&default_vec);
-        let max_less_than_j = obstacles.iter().filter(|&x| x.j < cursor.j).max();
-
-        match max_less_than_j {
-            Some(value) => {
-                let new_cursor = utils::CoOrd {
-                    i: value.i,
-                    j: value.j + 1,
-                };
-                if grid[[new_cursor.i, new_cursor.j]] == '⌞' {
-                    return (points_between(cursor, &new_cursor), 'O', new_cursor);
-                } else {
-                    grid[[new_cursor.i, new_cursor.j]] = '⌞';
-                }
-                return (points_between(cursor, &new_cursor), '#', new_cursor);
-            }
-            None => {
-                return (
-                    points_between(cursor, &utils::CoOrd { i: cursor.i, j: 0 }),
-                    '!',
-                    *cursor,
-                )
-            }
+        lab.extra = Some(location);
+        if cycle::find_repeat(guard, |g| lab.next_turn(g)).is_some() {
+            cycle_coords.insert(location);
         }
-    } else if direction == &Direction::Right {
-        let obstacles = obstacles_by_row.get(&cursor.i).unwrap_or(&default_vec);
-        let min_greater_than_j = obstacles.iter().filter(|&x| x.j > cursor.j).min();
-
-        match min_greater_than_j {
-            Some(value) => {
-                let new_cursor = utils::CoOrd {
-                    i: value.i,
-                    j: value.j - 1,
-                };
-                if grid[[new_cursor.i, new_cursor.j]] == '⌝' {
-                    return (points_between(cursor, &new_cursor), 'O', new_cursor);
-                } else {
-                    grid[[new_cursor.i, new_cursor.j]] = '⌝';
-                }
-                return (points_between(cursor, &new_cursor), '#', new_cursor);
+    }
+    for c in &cycle_coords {
+        debug!("An obstacle at {:?} makes a loop", c);
+    }
+    cycle_coords
+}
+
+// One straight stretch of the guard's walk, from where they turned into it to
+// where they turn again or step out of the lab
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Segment {
+    pub from: CoOrd,
+    pub to: CoOrd,
+    pub facing: Direction,
+}
+
+impl Segment {
+    pub fn points(&self) -> Vec<CoOrd> {
+        points_between(&self.from, &self.to)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Walk {
+    pub segments: Vec<Segment>,
+    // The guard came back round to a turn they'd already made
+    pub looped: bool,
+}
+
+// The guard's walk, with `extra` as one more obstacle
+pub fn walk(map: &GuardMap, extra: Option<CoOrd>) -> Walk {
+    let mut lab = Lab::new(&map.grid);
+    lab.extra = extra;
+    walk_lab(&lab, map.guard)
+}
+
+// Finds out up front whether the guard loops, and after how many turns, so the
+// walk can stop there without remembering every turn made on the way
+fn walk_lab(lab: &Lab, guard: Guard) -> Walk {
+    let repeat = cycle::brent(guard, |g| lab.next_turn(g));
+    let mut segments = Vec::new();
+    let mut guard = guard;
+    while repeat.is_none_or(|c| segments.len() < c.start + c.length) {
+        let (from, facing) = guard;
+        match lab.next_turn(&guard) {
+            Some(next) => {
+                segments.push(Segment {
+                    from,
+                    to: next.0,
+                    facing,
+                });
+                guard = next;
             }
             None => {
-                return (
-                    points_between(
-                        cursor,
-                        &utils::CoOrd {
-                            i: cursor.i,
-                            j: width - 1,
-                        },
-                    ),
-                    '!',
-                    *cursor,
-                )
+                segments.push(Segment {
+                    from,
+                    to: lab.exit(guard),
+                    facing,
+                });
+                break;
             }
         }
-    } else {
-        (vec![], '!', *cursor)
     }
+    Walk {
+        segments,
+        looped: repeat.is_some(),
+    }
+}
+
+// Every square the guard walks through before leaving. If they never leave,
+// the squares on their route so far.
+fn visited(lab: &Lab, guard: Guard) -> HashSet<CoOrd> {
+    walk_lab(lab, guard)
+        .segments
+        .iter()
+        .flat_map(Segment::points)
+        .collect()
+}
+
+// Records the walk a stretch at a time, with the guard drawn where each stretch
+// ends and `extra`, if there is one, drawn as an O
+pub fn replay(map: &GuardMap, extra: Option<CoOrd>, recorder: &mut Recorder) -> Walk {
+    let walk = walk(map, extra);
+    let mut lab = map.grid.clone();
+    if let Some(o) = extra {
+        lab[o] = 'O';
+    }
+    recorder.push(lab.as_array().clone(), HashSet::new());
+    lab[map.guard.0] = '.';
+    for segment in &walk.segments {
+        let mut frame = lab.clone();
+        // The guard only ever faces one of the four glyph directions
+        frame[segment.to] = segment.facing.glyph().unwrap_or('^');
+        recorder.push_segment(frame.into_array(), segment.from, segment.to);
+    }
+    walk
 }
 
 // Returns a vector of struct CoOrd { i: usize, j: usize } going in a straight line
 // from start to end, in the order you'd expect from those two values
 // Only works in straight lines.
-fn points_between(start: &utils::CoOrd, end: &utils::CoOrd) -> Vec<utils::CoOrd> {
+fn points_between(start: &CoOrd, end: &CoOrd) -> Vec<CoOrd> {
     let mut result = Vec::new();
     let static_i = start.i == end.i;
     let static_j = start.j == end.j;
@@ -224,26 +266,21 @@ fn points_between(start: &utils::CoOrd, end: &utils::CoOrd) -> Vec<utils::CoOrd>
     if static_i {
         let (min_j, max_j) = (min(start.j, end.j), max(start.j, end.j));
         for j in min_j..=max_j {
-            result.push(utils::CoOrd { i: start.i, j });
+            result.push(CoOrd { i: start.i, j });
         }
     } else if static_j {
         let (min_i, max_i) = (min(start.i, end.i), max(start.i, end.i));
         for i in min_i..=max_i {
-            result.push(utils::CoOrd { i, j: start.j });
+            result.push(CoOrd { i, j: start.j });
         }
     }
     result
 }
 
-fn find_guard(grid: &Array2<char>) -> utils::CoOrd {
-    let chars: Vec<char> = vec!['^', '>', '∨', '<'];
-    for guard in chars {
-        let possible = utils::locate_in_grid(grid, &guard);
-        if possible.is_some() {
-            return possible.unwrap();
-        }
-    }
-    return utils::CoOrd { i: 0, j: 0 };
+// Where the guard starts and which way they're facing
+fn find_guard(grid: &Grid<char>) -> Option<Guard> {
+    grid.iter()
+        .find_map(|(at, &c)| Direction::from_glyph(c).map(|facing| (at, facing)))
 }
 
 #[cfg(test)]
@@ -263,10 +300,49 @@ mod tests {
 #.........
 ......#..."#;
 
-        let result = guard_path(input);
+        let result = guard_path(input).unwrap();
         assert_eq!(result, 41);
     }
 
+    #[test]
+    fn test_guard_facing_right() {
+        let input = "....\n.>.#\n....";
+
+        let result = guard_path(input).unwrap();
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    fn test_no_guard() {
+        assert!(matches!(
+            Day06::parse("....\n.#..\n...."),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_walk() {
+        let map = Day06::parse(".#..\n...#\n#^..\n....").unwrap();
+        let walk = walk(&map, None);
+        assert!(!walk.looped);
+        assert_eq!(
+            walk.segments
+                .iter()
+                .map(|s| (s.to, s.facing))
+                .collect::<Vec<_>>(),
+            vec![
+                (CoOrd { i: 1, j: 1 }, Direction::Up),
+                (CoOrd { i: 1, j: 2 }, Direction::Right),
+                (CoOrd { i: 3, j: 2 }, Direction::Down),
+            ]
+        );
+
+        // Blocking the way out sends the guard round the same square for good
+        let looped = super::walk(&map, Some(CoOrd { i: 3, j: 2 }));
+        assert!(looped.looped);
+        assert_eq!(looped.segments.len(), 4);
+    }
+
     #[test]
     fn test_cycles() {
         let input = r#"....#.....
@@ -280,7 +356,7 @@ mod tests {
 #.........
 ......#..."#;
 
-        let result = cycles(input);
+        let result = cycles(input).unwrap();
         assert_eq!(result, 6);
     }
 }
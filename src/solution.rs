@@ -0,0 +1,160 @@
+use crate::error::Result;
+use std::fmt;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+// Days disagree on integer width (and some years need text), so answers are
+// widened into one type that the runner can print and compare
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $wide:ty, $($t:ty),+) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::$variant(value as $wide)
+                }
+            }
+        )+
+    };
+}
+
+answer_from!(Unsigned, u128, u32, u64, usize);
+answer_from!(Signed, i128, i32, i64, isize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    // The default reads everything into a String for parse; a day whose input
+    // can be taken a line at a time can stream it instead
+    fn parse_reader<R: BufRead>(mut reader: R) -> Result<Self::Input> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Self::parse(&input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+pub fn solve_part1<S: Solution>(input: &str) -> Result<Answer> {
+    S::part1(&S::parse(input)?)
+}
+
+pub fn solve_part2<S: Solution>(input: &str) -> Result<Answer> {
+    S::part2(&S::parse(input)?)
+}
+
+// How one part went, and how long it took with parsing included
+#[derive(Debug)]
+pub struct Outcome {
+    pub part: u8,
+    pub result: Result<Answer>,
+    pub elapsed: Duration,
+}
+
+// Parses the input once and runs each of `parts` on it. The parse counts
+// towards every part's time, as it would if the part were run on its own.
+pub fn solve_reader<S: Solution>(reader: &mut dyn BufRead, parts: &[u8]) -> Result<Vec<Outcome>> {
+    let start = Instant::now();
+    let input = S::parse_reader(reader)?;
+    let parsing = start.elapsed();
+    Ok(parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let result = match part {
+                1 => S::part1(&input),
+                _ => S::part2(&input),
+            };
+            Outcome {
+                part,
+                result,
+                elapsed: parsing + start.elapsed(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(41u32).to_string(), "41");
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from(String::from("1,2,3")).to_string(), "1,2,3");
+    }
+
+    // Adds up the numbers on each line; part 2 counts the lines
+    struct Sums;
+
+    impl Solution for Sums {
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input
+                .lines()
+                .map(|line| line.split(' ').filter_map(|n| n.parse::<u32>().ok()).sum())
+                .collect())
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().sum::<u32>().into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            Ok(input.len().into())
+        }
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let mut reader = "1 2\n3 4\n5\n".as_bytes();
+        let outcomes = solve_reader::<Sums>(&mut reader, &[2, 1]).unwrap();
+        let answers: Vec<(u8, Answer)> = outcomes
+            .into_iter()
+            .map(|o| (o.part, o.result.unwrap()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![(2, Answer::from(3usize)), (1, Answer::from(15u32))]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::client::Client;
+use crate::error::{Error, Result};
+use log::debug;
+use regex::Regex;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    // Wrong, but the site didn't say which way
+    Incorrect,
+    AlreadySolved,
+    RateLimited(Duration),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Incorrect => write!(f, "incorrect"),
+            Verdict::AlreadySolved => write!(f, "already-solved"),
+            Verdict::RateLimited(wait) => write!(f, "rate-limited ({}s)", wait.as_secs()),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "incorrect" => Ok(Verdict::Incorrect),
+            "already-solved" => Ok(Verdict::AlreadySolved),
+            _ => Err(Error::Parse(format!("unknown verdict {s:?}"))),
+        }
+    }
+}
+
+// Reads the <article> text AoC sends back after a POST to the answer endpoint
+pub fn parse_verdict(html: &str) -> Result<Verdict> {
+    if html.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if html.contains("You don't seem to be solving the right level") {
+        Ok(Verdict::AlreadySolved)
+    } else if html.contains("You gave an answer too recently") {
+        let rx = Regex::new(r"(?:(\d+)m )?(\d+)s left to wait").unwrap();
+        let wait = rx.captures(html).map_or(0, |c| {
+            let minutes: u64 = c.get(1).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+            let seconds: u64 = c[2].parse().unwrap_or(0);
+            minutes * 60 + seconds
+        });
+        Ok(Verdict::RateLimited(Duration::from_secs(wait)))
+    } else if html.contains("That's not the right answer") {
+        if html.contains("your answer is too high") {
+            Ok(Verdict::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Ok(Verdict::TooLow)
+        } else {
+            Ok(Verdict::Incorrect)
+        }
+    } else {
+        Err(Error::Parse(
+            "unrecognised response from the answer endpoint".to_string(),
+        ))
+    }
+}
+
+// Every verdict we've had back for a day, one `part<TAB>answer<TAB>verdict` per line
+pub struct Ledger {
+    path: PathBuf,
+    entries: Vec<(u8, String, Verdict)>,
+}
+
+impl Ledger {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut entries = Vec::new();
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines() {
+                let fields: Vec<&str> = line.split('\t').collect();
+                if let [part, answer, verdict] = fields[..] {
+                    entries.push((part.parse()?, answer.to_string(), verdict.parse()?));
+                }
+            }
+        }
+        Ok(Ledger { path, entries })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Judges an answer without asking the site, if what we already know allows it
+    pub fn check(&self, part: u8, answer: &str) -> Option<Verdict> {
+        let known: Vec<&(u8, String, Verdict)> =
+            self.entries.iter().filter(|(p, _, _)| *p == part).collect();
+        if let Some((_, _, verdict)) = known.iter().find(|(_, a, _)| a == answer) {
+            return Some(verdict.clone());
+        }
+        if known.iter().any(|(_, _, v)| *v == Verdict::Correct) {
+            return Some(Verdict::Incorrect);
+        }
+        let guess: i128 = answer.parse().ok()?;
+        let bound = |verdict: Verdict| {
+            known
+                .iter()
+                .filter(move |(_, _, v)| *v == verdict)
+                .filter_map(|(_, a, _)| a.parse::<i128>().ok())
+        };
+        if bound(Verdict::TooHigh).any(|high| guess >= high) {
+            Some(Verdict::TooHigh)
+        } else if bound(Verdict::TooLow).any(|low| guess <= low) {
+            Some(Verdict::TooLow)
+        } else {
+            None
+        }
+    }
+
+    // Rate limits and "not the right level" say nothing about the answer
+    // itself, so they aren't kept
+    pub fn record(&mut self, part: u8, answer: &str, verdict: &Verdict) -> Result<()> {
+        if let Verdict::RateLimited(_) | Verdict::AlreadySolved = verdict {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{part}\t{answer}\t{verdict}")?;
+        self.entries
+            .push((part, answer.to_string(), verdict.clone()));
+        Ok(())
+    }
+}
+
+pub struct Submission {
+    pub verdict: Verdict,
+    // True when the ledger answered and nothing was sent
+    pub cached: bool,
+}
+
+pub fn submit(client: &Client, day: u8, part: u8, answer: &str) -> Result<Submission> {
+    let mut ledger = Ledger::load(client.answers_path(day))?;
+    if let Some(verdict) = ledger.check(part, answer) {
+        debug!("Day {day} part {part}: {answer} already judged {verdict}");
+        return Ok(Submission {
+            verdict,
+            cached: true,
+        });
+    }
+    let verdict = parse_verdict(&client.post_answer(day, part, answer)?)?;
+    ledger.record(part, answer, &verdict)?;
+    Ok(Submission {
+        verdict,
+        cached: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{mock, Matcher};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_verdict() {
+        let right =
+            "<article><p>That's the right answer! You are one gold star closer.</p></article>";
+        let high = "<article><p>That's not the right answer; your answer is too high. If you're stuck...</p></article>";
+        let low = "<article><p>That's not the right answer; your answer is too low.</p></article>";
+        let wrong = "<article><p>That's not the right answer. If you're stuck...</p></article>";
+        let solved = "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>";
+        let limited = "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+
+        assert_eq!(parse_verdict(right).unwrap(), Verdict::Correct);
+        assert_eq!(parse_verdict(high).unwrap(), Verdict::TooHigh);
+        assert_eq!(parse_verdict(low).unwrap(), Verdict::TooLow);
+        assert_eq!(parse_verdict(wrong).unwrap(), Verdict::Incorrect);
+        assert_eq!(parse_verdict(solved).unwrap(), Verdict::AlreadySolved);
+        assert_eq!(
+            parse_verdict(limited).unwrap(),
+            Verdict::RateLimited(Duration::from_secs(65))
+        );
+        assert!(parse_verdict("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_ledger_bounds() {
+        let dir = tempdir().unwrap();
+        let mut ledger = Ledger::load(dir.path().join("day01.answers")).unwrap();
+        ledger.record(1, "500", &Verdict::TooHigh).unwrap();
+        ledger.record(1, "100", &Verdict::TooLow).unwrap();
+        ledger.record(1, "250", &Verdict::Incorrect).unwrap();
+
+        let reloaded = Ledger::load(ledger.path()).unwrap();
+        assert_eq!(reloaded.check(1, "600"), Some(Verdict::TooHigh));
+        assert_eq!(reloaded.check(1, "99"), Some(Verdict::TooLow));
+        assert_eq!(reloaded.check(1, "250"), Some(Verdict::Incorrect));
+        assert_eq!(reloaded.check(1, "300"), None);
+        assert_eq!(reloaded.check(2, "600"), None);
+    }
+
+    #[test]
+    fn test_wrong_answer_is_not_resubmitted() {
+        let m = mock("POST", "/2024/day/3/answer")
+            .match_header("cookie", "session=abc123")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("level".into(), "1".into()),
+                Matcher::UrlEncoded("answer".into(), "42".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                "<article><p>That's not the right answer; your answer is too low.</p></article>",
+            )
+            .expect(1)
+            .create();
+        let cache = tempdir().unwrap();
+        let client = Client::new(
+            &mockito::server_url(),
+            Some("abc123".to_string()),
+            cache.path(),
+        );
+
+        let first = submit(&client, 3, 1, "42").unwrap();
+        assert_eq!(first.verdict, Verdict::TooLow);
+        assert!(!first.cached);
+        let again = submit(&client, 3, 1, "42").unwrap();
+        assert_eq!(again.verdict, Verdict::TooLow);
+        assert!(again.cached);
+        let lower = submit(&client, 3, 1, "7").unwrap();
+        assert_eq!(lower.verdict, Verdict::TooLow);
+        assert!(lower.cached);
+        m.assert();
+    }
+
+    #[test]
+    fn test_wrong_level_is_not_recorded() {
+        let m = mock("POST", "/2024/day/4/answer")
+            .match_header("cookie", "session=abc123")
+            .with_status(200)
+            .with_body("<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>")
+            .expect(2)
+            .create();
+        let cache = tempdir().unwrap();
+        let client = Client::new(
+            &mockito::server_url(),
+            Some("abc123".to_string()),
+            cache.path(),
+        );
+
+        let first = submit(&client, 4, 2, "1234").unwrap();
+        assert_eq!(first.verdict, Verdict::AlreadySolved);
+        let again = submit(&client, 4, 2, "1234").unwrap();
+        assert_eq!(again.verdict, Verdict::AlreadySolved);
+        assert!(!again.cached);
+        m.assert();
+    }
+}
@@ -1,65 +1,115 @@
-#[path = "utils.rs"]
-mod utils;
-use core::ops::Range;
-use regex::Captures;
-use regex::Regex;
-
-pub fn uncorrupt(input: &str) -> i32 {
-    let rx = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let captures: Vec<Captures> = rx.captures_iter(input).collect();
-    captures.iter().fold(0, |acc, x| acc + do_mult(x))
-}
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use std::ops::Range;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    // Everything in the corrupted memory that means something, in order
+    type Input = Vec<Token>;
 
-pub fn dodont(input: &str) -> i32 {
-    let dont_indices: Vec<usize> = input
-        .match_indices("don't()")
-        .map(|(index, _)| index)
-        .collect();
-    let do_indices: Vec<usize> = input
-        .match_indices("do()")
-        .map(|(index, _)| index)
-        .collect();
-    let ranges = construct_ranges(dont_indices, do_indices, input.len());
-    let mut cleaned = String::new();
-    for range in ranges {
-        cleaned.push_str(&input[range.clone()]);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(tokenize(input))
     }
-    let rx = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let captures: Vec<Captures> = rx.captures_iter(&cleaned).collect();
-    captures.iter().fold(0, |acc, x| acc + do_mult(x))
-}
 
-fn construct_ranges(donts: Vec<usize>, dos: Vec<usize>, max: usize) -> Vec<Range<usize>> {
-    let mut ranges = Vec::new();
-    let final_do = dos.last().unwrap();
-    let final_dont = donts.last().unwrap();
-    let stopping_point = if final_dont < final_do {
-        max
-    } else {
-        *final_dont
-    };
-    if !donts.is_empty() {
-        ranges.push(0..donts[0]);
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(run(input, false).into())
     }
-    let mut last_pushed_dont = donts[0];
-    if !dos.is_empty() {
-        for do_index in dos {
-            if do_index >= stopping_point {
-            } else if do_index < last_pushed_dont {
-            } else {
-                let dont = donts.iter().find(|&x| x > &do_index).unwrap_or(&max);
-                ranges.push(do_index..*dont);
-                last_pushed_dont = *dont;
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(run(input, true).into())
+    }
+}
+
+pub fn uncorrupt(input: &str) -> Result<i32> {
+    Ok(run(&Day03::parse(input)?, false))
+}
+
+pub fn dodont(input: &str) -> Result<i32> {
+    Ok(run(&Day03::parse(input)?, true))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Instruction {
+    Mul(i32, i32),
+    Do,
+    Dont,
+}
+
+// An instruction and the bytes of the input it was read from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    pub instruction: Instruction,
+    pub span: Range<usize>,
+}
+
+// One pass over the input. Wherever an instruction doesn't quite parse, the
+// scan carries on from the next byte, so "mul(mul(2,3)" still finds mul(2,3).
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut at = 0;
+    while at < bytes.len() {
+        match instruction(&bytes[at..]) {
+            Some((instruction, length)) => {
+                tokens.push(Token {
+                    instruction,
+                    span: at..at + length,
+                });
+                at += length;
             }
+            None => at += 1,
         }
     }
-    ranges
+    tokens
+}
+
+// The instruction at the very start of `bytes`, and how many bytes it takes up
+fn instruction(bytes: &[u8]) -> Option<(Instruction, usize)> {
+    if bytes.starts_with(b"do()") {
+        return Some((Instruction::Do, 4));
+    }
+    if bytes.starts_with(b"don't()") {
+        return Some((Instruction::Dont, 7));
+    }
+    let rest = bytes.strip_prefix(b"mul(")?;
+    let (left, rest) = number(rest)?;
+    let rest = rest.strip_prefix(b",")?;
+    let (right, rest) = number(rest)?;
+    let rest = rest.strip_prefix(b")")?;
+    Some((Instruction::Mul(left, right), bytes.len() - rest.len()))
 }
 
-fn do_mult(captures: &Captures) -> i32 {
-    let left: i32 = captures[1].parse().unwrap();
-    let right: i32 = captures[2].parse().unwrap();
-    left * right
+// A number of one to three digits and whatever follows it
+fn number(bytes: &[u8]) -> Option<(i32, &[u8])> {
+    let digits = bytes
+        .iter()
+        .take(3)
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return None;
+    }
+    let value = bytes[..digits]
+        .iter()
+        .fold(0, |acc, b| acc * 10 + i32::from(b - b'0'));
+    Some((value, &bytes[digits..]))
+}
+
+// Adds up the products of every enabled mul. The machine starts enabled; when
+// `conditionals` is off (part 1) do() and don't() are ignored.
+pub fn run(tokens: &[Token], conditionals: bool) -> i32 {
+    let mut enabled = true;
+    let mut total = 0;
+    for token in tokens {
+        match token.instruction {
+            Instruction::Mul(left, right) if enabled => total += left * right,
+            Instruction::Mul(..) => {}
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = !conditionals,
+        }
+    }
+    total
 }
 
 #[cfg(test)]
@@ -70,7 +120,7 @@ mod tests {
     fn test_uncorrupt() {
         let input = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
 
-        let result = uncorrupt(input);
+        let result = uncorrupt(input).unwrap();
         assert_eq!(result, 161);
     }
 
@@ -78,7 +128,50 @@ mod tests {
     fn test_dodont() {
         let input = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"#;
 
-        let result = dodont(input);
+        let result = dodont(input).unwrap();
         assert_eq!(result, 48);
     }
+
+    #[test]
+    fn test_dodont_without_markers() {
+        assert_eq!(dodont("mul(2,4)mul(3,3)").unwrap(), 17);
+        assert_eq!(dodont("mul(2,4)don't()mul(3,3)").unwrap(), 8);
+        assert_eq!(dodont("do()mul(2,4)do()mul(3,3)").unwrap(), 17);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("xmul(2,4)%don't()_mul(5,5)+mul(32,64]do()mul(1234,5)");
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    instruction: Instruction::Mul(2, 4),
+                    span: 1..9,
+                },
+                Token {
+                    instruction: Instruction::Dont,
+                    span: 10..17,
+                },
+                Token {
+                    instruction: Instruction::Mul(5, 5),
+                    span: 18..26,
+                },
+                Token {
+                    instruction: Instruction::Do,
+                    span: 37..41,
+                },
+            ]
+        );
+        // A broken instruction doesn't swallow the one that starts inside it
+        let nested = tokenize("mul(mul(2,3)");
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].span, 4..12);
+    }
+
+    #[test]
+    fn test_dodont_does_not_join_spans() {
+        // Cutting out the disabled stretch mustn't leave a mul( behind to pair up
+        assert_eq!(dodont("mul(2,don't()mul(9,9)do()3)").unwrap(), 0);
+    }
 }
@@ -1,12 +1,24 @@
+use crate::error::{Error, Result};
 use log::debug;
 use ndarray::Array2;
 use reqwest::blocking::get;
-use reqwest::Error;
-use std::collections::HashMap;
 use std::fs::File;
-use std::io::{self, Read};
-
-pub fn url_to_string(url: &str) -> Result<String, Error> {
+use std::io::Read;
+
+pub mod cycle;
+mod direction;
+mod grid;
+pub mod parse;
+mod range_set;
+pub mod search;
+mod vec2;
+
+pub use direction::Direction;
+pub use grid::Grid;
+pub use range_set::RangeSet;
+pub use vec2::Vec2;
+
+pub fn url_to_string(url: &str) -> Result<String> {
     debug!("URL is {url}");
     let response = get(url)?;
     let status = response.status();
@@ -17,11 +29,14 @@ pub fn url_to_string(url: &str) -> Result<String, Error> {
         debug!("Body is {body}");
         Ok(body)
     } else {
-        Ok(String::new())
+        Err(Error::Http {
+            url: url.to_string(),
+            status,
+        })
     }
 }
 
-pub fn file_input(file_path: &str) -> io::Result<String> {
+pub fn file_input(file_path: &str) -> Result<String> {
     let mut file = File::open(file_path)?;
     let mut contents = String::new();
 
@@ -30,20 +45,51 @@ pub fn file_input(file_path: &str) -> io::Result<String> {
     Ok(contents)
 }
 
-pub fn vec_to_array2<T>(vec_of_vecs: Vec<Vec<T>>) -> Array2<T>
+// Every row has to be as long as the first; the error names the first that isn't
+pub fn vec_to_array2<T>(vec_of_vecs: Vec<Vec<T>>) -> Result<Array2<T>> {
+    let width = vec_of_vecs.first().map_or(0, Vec::len);
+    if let Some(i) = vec_of_vecs.iter().position(|row| row.len() != width) {
+        return Err(Error::InvalidInput(format!(
+            "row {} has {} cells but row 1 has {width}",
+            i + 1,
+            vec_of_vecs[i].len()
+        )));
+    }
+    let height = vec_of_vecs.len();
+    let flattened: Vec<T> = vec_of_vecs.into_iter().flatten().collect();
+    Array2::from_shape_vec((height, width), flattened)
+        .map_err(|e| Error::InvalidInput(e.to_string()))
+}
+
+// As vec_to_array2, but short rows are filled out with `fill` to the longest row
+pub fn vec_to_array2_padded<T>(vec_of_vecs: Vec<Vec<T>>, fill: T) -> Result<Array2<T>>
 where
     T: Clone,
 {
-    let flattened: Vec<T> = vec_of_vecs.clone().into_iter().flatten().collect();
-    let rows = flattened.len() / vec_of_vecs.len();
-    Array2::from_shape_vec((vec_of_vecs.len(), rows), flattened).unwrap()
+    let width = vec_of_vecs.iter().map(Vec::len).max().unwrap_or(0);
+    let padded = vec_of_vecs
+        .into_iter()
+        .map(|mut row| {
+            row.resize(width, fill.clone());
+            row
+        })
+        .collect();
+    vec_to_array2(padded)
 }
 
 pub fn string_to_2d_array<F, T>(input: &str, manipulation: F) -> Vec<Vec<T>>
 where
     F: Fn(&str) -> Vec<T>,
 {
-    input.lines().map(|line| manipulation(line)).collect()
+    input.lines().map(manipulation).collect()
+}
+
+// As string_to_2d_array, for row parsers that can fail, e.g. parse::words
+pub fn try_string_to_2d_array<F, T>(input: &str, manipulation: F) -> Result<Vec<Vec<T>>>
+where
+    F: Fn(parse::Line) -> Result<Vec<T>>,
+{
+    parse::lines(input).map(manipulation).collect()
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -63,191 +109,50 @@ impl Line {
         Line { p1, p2 }
     }
 
-    pub fn extend_back(&self, d_i: usize, d_j: usize, grid_size: (usize, usize)) -> Option<CoOrd> {
-        let mut before_i = 0;
-        if self.p1.i < self.p2.i {
-            // Will put us over `max` if we're out of bounds
-            before_i = self.p1.i.wrapping_sub(d_i);
-        } else {
-            before_i = self.p1.i + d_i;
-        }
-        let mut before_j = 0;
-        if self.p1.j < self.p2.j {
-            before_j = self.p1.j.wrapping_sub(d_j);
-        } else {
-            before_j = self.p1.j + d_j;
-        }
-
-        let before = if (before_i) >= 0
-            && (before_j) >= 0
-            && (before_i as usize) < grid_size.0
-            && (before_j as usize) < grid_size.1
-        {
-            Some(CoOrd {
-                i: before_i as usize,
-                j: before_j as usize,
-            })
-        } else {
-            None
-        };
-        before
+    // The step from p1 to p2
+    pub fn delta(&self) -> Vec2 {
+        self.p2 - self.p1
     }
 
-    pub fn extend_forward(
-        &self,
-        d_i: usize,
-        d_j: usize,
-        grid_size: (usize, usize),
-    ) -> Option<CoOrd> {
-        let mut after_i = 0;
-        if self.p1.i < self.p2.i {
-            // Will put us over `max` if we're out of bounds
-            after_i = self.p2.i + d_i;
-        } else {
-            after_i = self.p2.i.wrapping_sub(d_i);
-        }
-        let mut after_j = 0;
-        if self.p1.j < self.p2.j {
-            after_j = self.p2.j + d_j;
-        } else {
-            after_j = self.p2.j.wrapping_sub(d_j);
-        }
+    // The point `times` steps back from p1, if it's still on the grid
+    pub fn extend_back(&self, times: i64, grid_size: (usize, usize)) -> Option<CoOrd> {
+        self.p1.checked_add(-self.delta() * times, grid_size)
+    }
 
-        let after = if (after_i) >= 0
-            && (after_j) >= 0
-            && (after_i as usize) < grid_size.0
-            && (after_j as usize) < grid_size.1
-        {
-            Some(CoOrd {
-                i: after_i as usize,
-                j: after_j as usize,
-            })
-        } else {
-            None
-        };
-        after
+    // The point `times` steps on from p2, if it's still on the grid
+    pub fn extend_forward(&self, times: i64, grid_size: (usize, usize)) -> Option<CoOrd> {
+        self.p2.checked_add(self.delta() * times, grid_size)
     }
 
-    pub fn extend_back_greedy(
-        &self,
-        d_i: usize,
-        d_j: usize,
-        grid_size: (usize, usize),
-    ) -> Vec<CoOrd> {
-        let mut points: Vec<CoOrd> = Vec::new();
-        let mut in_bounds = true;
-        let mut local_d_i = d_i.clone();
-        let mut local_d_j = d_j.clone();
-        while in_bounds {
-            let next_point = self.extend_back(local_d_i, local_d_j, grid_size);
-            match next_point {
-                Some(point) => points.push(point),
-                None => in_bounds = false,
-            }
-            local_d_i += d_i;
-            local_d_j += d_j;
-        }
-        points
+    pub fn extend_back_greedy(&self, grid_size: (usize, usize)) -> Vec<CoOrd> {
+        (1..)
+            .map_while(|times| self.extend_back(times, grid_size))
+            .collect()
     }
 
-    pub fn extend_forward_greedy(
-        &self,
-        d_i: usize,
-        d_j: usize,
-        grid_size: (usize, usize),
-    ) -> Vec<CoOrd> {
-        let mut points: Vec<CoOrd> = Vec::new();
-        points.push(self.p1);
-        points.push(self.p2);
-        let mut in_bounds = true;
-        let mut local_d_i = d_i.clone();
-        let mut local_d_j = d_j.clone();
-        while in_bounds {
-            let next_point = self.extend_forward(local_d_i, local_d_j, grid_size);
-            match next_point {
-                Some(point) => points.push(point),
-                None => in_bounds = false,
-            }
-            local_d_i += d_i;
-            local_d_j += d_j;
-        }
+    // Includes both ends of the line itself
+    pub fn extend_forward_greedy(&self, grid_size: (usize, usize)) -> Vec<CoOrd> {
+        let mut points = vec![self.p1, self.p2];
+        points.extend((1..).map_while(|times| self.extend_forward(times, grid_size)));
         points
     }
 
     pub fn extend(&self, grid_size: (usize, usize)) -> (Option<CoOrd>, Option<CoOrd>) {
-        let (d_i, d_j) = distance_between(self.p1, self.p2);
-        let before = self.extend_back(d_i, d_j, grid_size);
-        let after = self.extend_forward(d_i, d_j, grid_size);
-
-        (before, after)
+        (
+            self.extend_back(1, grid_size),
+            self.extend_forward(1, grid_size),
+        )
     }
 }
 
-//pub fn extend_until(line: &Line, grid_size: (usize, usize)) -> (Option<CoOrd>, Option<CoOrd>) {
-//    let mut back: Vec<CoOrd> = Vec::new();
-//    let mut front: Vec<CoOrd> = Vec::new();
-//    let mut in_bounds = true;
-//    while in_bounds {
-//        let (back, front) = self.extend((max, max));
-//        if back.is_some() {
-//            back.push(back.unwrap());
-//        }
-//        if front.is_some() {
-//            front.push(front.unwrap());
-//        }
-//        if back.is_none() && front.is_none {
-//            in_bounds = true;
-//        }
-//    }
-//}
-
-pub fn distance_between(a: CoOrd, b: CoOrd) -> (usize, usize) {
-    (
-        (b.i as i32 - a.i as i32).abs() as usize,
-        (b.j as i32 - a.j as i32).abs() as usize,
-    )
-}
-
-// Assumes you're looking for all matches of `target`
-pub fn locate_all_in_grid<T>(
-    grid: &Array2<T>,
-    target: &T,
-) -> (HashMap<usize, Vec<CoOrd>>, HashMap<usize, Vec<CoOrd>>)
-where
-    T: PartialEq,
-{
-    let mut row_matches = HashMap::new();
-    let mut col_matches = HashMap::new();
-
-    grid.indexed_iter()
-        .filter(|(_, value)| *value == target)
-        .for_each(|((i, j), _)| {
-            let coord = CoOrd { i, j };
-            row_matches.entry(i).or_insert_with(Vec::new).push(coord);
-            col_matches.entry(j).or_insert_with(Vec::new).push(coord);
-        });
-
-    (row_matches, col_matches)
-}
-
-// Assumes you're looking for exactly one `target`
-pub fn locate_in_grid<T>(grid: &Array2<T>, target: &T) -> Option<CoOrd>
-where
-    T: PartialEq,
-{
-    grid.indexed_iter()
-        .find(|(_, value)| *value == target)
-        .map(|((i, j), _)| CoOrd { i, j })
-}
-
 pub fn just_chars(string: &str) -> Vec<char> {
     string.chars().collect()
 }
 
-fn digits(original: &str) -> Vec<i32> {
+pub fn digits(original: &str) -> Vec<i32> {
     original
         .chars()
-        .filter(|&c| c.is_digit(10))
+        .filter(|&c| c.is_ascii_digit())
         .map(|c| c.to_digit(10).unwrap() as i32)
         .collect()
 }
@@ -271,6 +176,16 @@ mod tests {
         assert_eq!(result, "Hello, world!");
     }
 
+    #[test]
+    fn test_url_to_string_error_status() {
+        let _m = mock("GET", "/missing").with_status(404).create();
+
+        let url = format!("{}/missing", mockito::server_url());
+        let result = url_to_string(&url);
+
+        assert!(matches!(result, Err(Error::Http { status, .. }) if status == 404));
+    }
+
     #[test]
     fn string_conversion() {
         let input = r#"1abc2
@@ -278,14 +193,47 @@ pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet"#;
         let result = string_to_2d_array(input, digits);
-        let mut nd_array: Vec<Vec<i32>> = Vec::new();
-        nd_array.push(vec![1, 2]);
-        nd_array.push(vec![3, 8]);
-        nd_array.push(vec![1, 2, 3, 4, 5]);
-        nd_array.push(vec![7]);
+        let nd_array: Vec<Vec<i32>> = vec![vec![1, 2], vec![3, 8], vec![1, 2, 3, 4, 5], vec![7]];
         assert_eq!(result, nd_array);
     }
 
+    #[test]
+    fn fallible_string_conversion() {
+        let rows = try_string_to_2d_array("1 2\n3 4 5", parse::words::<u8>).unwrap();
+        assert_eq!(rows, vec![vec![1, 2], vec![3, 4, 5]]);
+        let error = try_string_to_2d_array("1 2\n3 -4", parse::words::<u8>).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Syntax {
+                line: 2,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_vec_to_array2() {
+        let square = vec_to_array2(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(square, ndarray::array![[1, 2], [3, 4]]);
+
+        let ragged = vec![vec!['a', 'b', 'c'], vec!['d'], vec!['e', 'f', 'g']];
+        let error = vec_to_array2(ragged.clone()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid puzzle input: row 2 has 1 cells but row 1 has 3"
+        );
+        let padded = vec_to_array2_padded(ragged, '.').unwrap();
+        assert_eq!(
+            padded,
+            ndarray::array![['a', 'b', 'c'], ['d', '.', '.'], ['e', 'f', 'g']]
+        );
+
+        // A blank line at the end is a row of its own, not part of the grid
+        let trailing = string_to_2d_array("ab\ncd\n\n", just_chars);
+        assert!(vec_to_array2(trailing).is_err());
+    }
+
     #[test]
     fn test_file_input() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -307,8 +255,8 @@ treb7uchet"#;
             p2: second,
         };
         let (back, front) = line.extend((7, 7));
-        if back.is_some() && front.is_some() {
-            let expected = Line::new(back.unwrap(), front.unwrap());
+        if let (Some(back), Some(front)) = (back, front) {
+            let expected = Line::new(back, front);
             let e1 = CoOrd { i: 1, j: 0 };
             let e2 = CoOrd { i: 4, j: 6 };
             assert_eq!(expected.p1, e1);
@@ -325,8 +273,8 @@ treb7uchet"#;
             p2: second,
         };
         let (back, front) = line.extend((7, 7));
-        if back.is_some() && front.is_some() {
-            let expected = Line::new(back.unwrap(), front.unwrap());
+        if let (Some(back), Some(front)) = (back, front) {
+            let expected = Line::new(back, front);
             let e1 = CoOrd { i: 2, j: 10 };
             let e2 = CoOrd { i: 5, j: 1 };
             assert_eq!(expected.p1, e1);
@@ -335,43 +283,19 @@ treb7uchet"#;
     }
 
     #[test]
-    fn test_distance_between() {
-        let first = CoOrd { i: 1, j: 1 };
-        let second = CoOrd { i: 2, j: 3 };
-        let result = distance_between(first, second);
-        assert_eq!(result, (1, 2));
-        let reverse = distance_between(second, first);
-        assert_eq!(reverse, (1, 2));
-        let same = distance_between(first, first);
-        assert_eq!(same, (0, 0));
-    }
-
-    #[test]
-    fn test_find_all_in_grid() {
-        let input = r#".....
-.g...
-...g.
-....."#;
-        let grid = vec_to_array2(string_to_2d_array(input, just_chars));
-        let result = locate_all_in_grid(&grid, &'g');
-        let mut expected_row = HashMap::new();
-        expected_row.insert(1, vec![CoOrd { i: 1, j: 1 }]);
-        expected_row.insert(2, vec![CoOrd { i: 2, j: 3 }]);
-        let mut expected_column = HashMap::new();
-        expected_column.insert(1, vec![CoOrd { i: 1, j: 1 }]);
-        expected_column.insert(3, vec![CoOrd { i: 2, j: 3 }]);
-        assert_eq!(result, (expected_row, expected_column));
-    }
-
-    #[test]
-    fn test_find_in_grid() {
-        let input = r#".....
-.....
-...g.
-....."#;
-        let grid = vec_to_array2(string_to_2d_array(input, just_chars));
-        let result = locate_in_grid(&grid, &'g');
-
-        assert_eq!(result.unwrap(), CoOrd { i: 2, j: 3 });
+    fn test_extend_greedy() {
+        let line = Line::new(CoOrd { i: 3, j: 3 }, CoOrd { i: 2, j: 4 });
+        assert_eq!(
+            line.extend_back_greedy((6, 6)),
+            vec![CoOrd { i: 4, j: 2 }, CoOrd { i: 5, j: 1 }]
+        );
+        assert_eq!(
+            line.extend_forward_greedy((6, 6)),
+            vec![
+                CoOrd { i: 3, j: 3 },
+                CoOrd { i: 2, j: 4 },
+                CoOrd { i: 1, j: 5 }
+            ]
+        );
     }
 }
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A sequence x0, x1 = step(x0), ... that settles into a loop. `start` is the
+// index of the first state on the loop and `length` is how many states it has.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+// All three finders below treat `step` returning None as the sequence ending,
+// in which case there's no cycle. `step` has to be deterministic.
+
+// Remembers every state, so it needs Hash but only walks the sequence once
+pub fn find_repeat<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = initial;
+    for index in 0.. {
+        if let Some(&first) = seen.get(&state) {
+            return Some(Cycle {
+                start: first,
+                length: index - first,
+            });
+        }
+        let next = step(&state)?;
+        seen.insert(state, index);
+        state = next;
+    }
+    None
+}
+
+// Tortoise and hare: constant memory, at the cost of stepping some states twice
+pub fn floyd<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        let halfway = step(&hare)?;
+        hare = step(&halfway)?;
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+// Also constant memory, and usually fewer steps than floyd
+pub fn brent<S, F>(initial: S, mut step: F) -> Option<Cycle>
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> Option<S>,
+{
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn rho(x: &u32) -> Option<u32> {
+        Some(if *x == 5 { 2 } else { x + 1 })
+    }
+
+    fn ends(x: &u32) -> Option<u32> {
+        (*x < 10).then(|| x + 1)
+    }
+
+    #[test]
+    fn test_finders_agree() {
+        let expected = Some(Cycle {
+            start: 2,
+            length: 4,
+        });
+        assert_eq!(find_repeat(0, rho), expected);
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+
+        let fixed_point = Some(Cycle {
+            start: 0,
+            length: 1,
+        });
+        assert_eq!(find_repeat(7, |&x| Some(x)), fixed_point);
+        assert_eq!(floyd(7, |&x| Some(x)), fixed_point);
+        assert_eq!(brent(7, |&x| Some(x)), fixed_point);
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        assert_eq!(find_repeat(0, ends), None);
+        assert_eq!(floyd(0, ends), None);
+        assert_eq!(brent(0, ends), None);
+    }
+}
@@ -0,0 +1,120 @@
+use super::Vec2;
+
+// Compass headings on a grid where i grows downwards and j grows to the right
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    // Clockwise from up
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub fn delta(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(-1, 0),
+            Direction::UpRight => Vec2::new(-1, 1),
+            Direction::Right => Vec2::new(0, 1),
+            Direction::DownRight => Vec2::new(1, 1),
+            Direction::Down => Vec2::new(1, 0),
+            Direction::DownLeft => Vec2::new(1, -1),
+            Direction::Left => Vec2::new(0, -1),
+            Direction::UpLeft => Vec2::new(-1, -1),
+        }
+    }
+
+    // Eighths of a turn clockwise, negative for anticlockwise
+    fn rotate(self, eighths: isize) -> Direction {
+        let index = Direction::ALL.iter().position(|&d| d == self).unwrap() as isize;
+        Direction::ALL[(index + eighths).rem_euclid(8) as usize]
+    }
+
+    // A quarter turn, so orthogonal headings stay orthogonal
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        !Direction::ORTHOGONAL.contains(&self)
+    }
+
+    // How the puzzles draw a guard facing each way
+    pub fn from_glyph(glyph: char) -> Option<Direction> {
+        match glyph {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn glyph(self) -> Option<char> {
+        match self {
+            Direction::Up => Some('^'),
+            Direction::Right => Some('>'),
+            Direction::Down => Some('v'),
+            Direction::Left => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turning() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpLeft.turn_right(), Direction::UpRight);
+        assert_eq!(Direction::DownRight.reverse(), Direction::UpLeft);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.reverse().delta(), -d.delta());
+        }
+    }
+
+    #[test]
+    fn test_glyphs() {
+        for d in Direction::ORTHOGONAL {
+            assert_eq!(d.glyph().and_then(Direction::from_glyph), Some(d));
+        }
+        assert_eq!(Direction::from_glyph('v'), Some(Direction::Down));
+        assert_eq!(Direction::from_glyph('#'), None);
+        assert!(Direction::DownLeft.is_diagonal());
+        assert!(!Direction::Down.is_diagonal());
+    }
+}
@@ -0,0 +1,244 @@
+use super::{vec_to_array2, CoOrd, Direction, Vec2};
+use crate::error::{Error, Result};
+use ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+// A rectangular grid addressed by CoOrd, with i as the row and j as the column
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(cells: Array2<T>) -> Self {
+        Grid { cells }
+    }
+
+    // Every row has to be as long as the first
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        if rows.first().is_none_or(Vec::is_empty) {
+            return Err(Error::InvalidInput("the grid is empty".to_string()));
+        }
+        Ok(Grid {
+            cells: vec_to_array2(rows)?,
+        })
+    }
+
+    // One cell per character, one row per line
+    pub fn parse_with<F>(input: &str, cell: F) -> Result<Self>
+    where
+        F: Fn(char) -> Result<T>,
+    {
+        let rows = input
+            .lines()
+            .map(|line| line.chars().map(&cell).collect::<Result<Vec<T>>>())
+            .collect::<Result<Vec<Vec<T>>>>()?;
+        Grid::from_rows(rows)
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    // (height, width), the same order Line::extend expects
+    pub fn size(&self) -> (usize, usize) {
+        (self.height(), self.width())
+    }
+
+    pub fn contains(&self, at: CoOrd) -> bool {
+        at.i < self.height() && at.j < self.width()
+    }
+
+    pub fn get(&self, at: CoOrd) -> Option<&T> {
+        self.cells.get((at.i, at.j))
+    }
+
+    pub fn get_mut(&mut self, at: CoOrd) -> Option<&mut T> {
+        self.cells.get_mut((at.i, at.j))
+    }
+
+    // The co-ord one `step` away from `from`, if that's on the grid
+    pub fn offset(&self, from: CoOrd, step: Vec2) -> Option<CoOrd> {
+        from.checked_add(step, self.size())
+    }
+
+    // Up, right, down and left of `at`, skipping any off the edge
+    pub fn neighbours4(&self, at: CoOrd) -> impl Iterator<Item = CoOrd> + '_ {
+        Direction::ORTHOGONAL
+            .iter()
+            .filter_map(move |d| self.offset(at, d.delta()))
+    }
+
+    // As neighbours4, plus the diagonals
+    pub fn neighbours8(&self, at: CoOrd) -> impl Iterator<Item = CoOrd> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |d| self.offset(at, d.delta()))
+    }
+
+    pub fn row(&self, i: usize) -> ArrayView1<'_, T> {
+        self.cells.row(i)
+    }
+
+    pub fn column(&self, j: usize) -> ArrayView1<'_, T> {
+        self.cells.column(j)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = ArrayView1<'_, T>> {
+        self.cells.axis_iter(Axis(0))
+    }
+
+    // Row by row, left to right
+    pub fn iter(&self) -> impl Iterator<Item = (CoOrd, &T)> {
+        self.cells
+            .indexed_iter()
+            .map(|((i, j), value)| (CoOrd { i, j }, value))
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = CoOrd> {
+        let width = self.width();
+        (0..self.height()).flat_map(move |i| (0..width).map(move |j| CoOrd { i, j }))
+    }
+
+    pub fn as_array(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.cells
+    }
+}
+
+// So helpers that take any 2D array, like search::open_neighbours, take a Grid too
+impl<'a, T> From<&'a Grid<T>> for ArrayView2<'a, T> {
+    fn from(grid: &'a Grid<T>) -> Self {
+        grid.cells.view()
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    // The first `target` reading row by row
+    pub fn find(&self, target: &T) -> Option<CoOrd> {
+        self.iter()
+            .find(|(_, value)| *value == target)
+            .map(|(at, _)| at)
+    }
+
+    pub fn find_all(&self, target: &T) -> Vec<CoOrd> {
+        self.iter()
+            .filter(|(_, value)| *value == target)
+            .map(|(at, _)| at)
+            .collect()
+    }
+
+    // Every `target`, grouped once by row and again by column
+    pub fn find_all_by_line(
+        &self,
+        target: &T,
+    ) -> (HashMap<usize, Vec<CoOrd>>, HashMap<usize, Vec<CoOrd>>) {
+        let mut row_matches = HashMap::new();
+        let mut col_matches = HashMap::new();
+        for at in self.find_all(target) {
+            row_matches.entry(at.i).or_insert_with(Vec::new).push(at);
+            col_matches.entry(at.j).or_insert_with(Vec::new).push(at);
+        }
+        (row_matches, col_matches)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Result<Self> {
+        Grid::parse_with(input, Ok)
+    }
+}
+
+impl<T> Index<CoOrd> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, at: CoOrd) -> &T {
+        &self.cells[[at.i, at.j]]
+    }
+}
+
+impl<T> IndexMut<CoOrd> for Grid<T> {
+    fn index_mut(&mut self, at: CoOrd) -> &mut T {
+        &mut self.cells[[at.i, at.j]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANTENNAS: &str = r#".....
+.g...
+...g.
+....."#;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(ANTENNAS).unwrap();
+        assert_eq!(grid.size(), (4, 5));
+        assert_eq!(grid.get(CoOrd { i: 1, j: 1 }), Some(&'g'));
+        assert_eq!(grid.get(CoOrd { i: 4, j: 0 }), None);
+        assert_eq!(grid.row(2).iter().collect::<String>(), "...g.");
+        assert_eq!(grid.column(3).iter().collect::<String>(), "..g.");
+
+        let digits = Grid::parse_with("12\n34", |c| {
+            c.to_digit(10)
+                .ok_or_else(|| Error::Parse(format!("{c:?} is not a digit")))
+        })
+        .unwrap();
+        assert_eq!(digits[CoOrd { i: 1, j: 0 }], 3);
+        assert!(Grid::parse_with("1x", |c| c
+            .to_digit(10)
+            .ok_or_else(|| Error::Parse(format!("{c:?} is not a digit"))))
+        .is_err());
+    }
+
+    #[test]
+    fn test_ragged_and_empty() {
+        let error = Grid::parse("...\n..\n...").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid puzzle input: row 2 has 2 cells but row 1 has 3"
+        );
+        assert!(Grid::parse("").is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::parse(ANTENNAS).unwrap();
+        let corner: Vec<CoOrd> = grid.neighbours4(CoOrd { i: 0, j: 0 }).collect();
+        assert_eq!(corner, vec![CoOrd { i: 0, j: 1 }, CoOrd { i: 1, j: 0 }]);
+        assert_eq!(grid.neighbours8(CoOrd { i: 0, j: 0 }).count(), 3);
+        assert_eq!(grid.neighbours4(CoOrd { i: 1, j: 1 }).count(), 4);
+        assert_eq!(grid.neighbours8(CoOrd { i: 1, j: 1 }).count(), 8);
+        assert_eq!(grid.neighbours8(CoOrd { i: 3, j: 4 }).count(), 3);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::parse(ANTENNAS).unwrap();
+        assert_eq!(grid.find(&'g'), Some(CoOrd { i: 1, j: 1 }));
+        assert_eq!(grid.find(&'#'), None);
+        assert_eq!(
+            grid.find_all(&'g'),
+            vec![CoOrd { i: 1, j: 1 }, CoOrd { i: 2, j: 3 }]
+        );
+
+        let (by_row, by_column) = grid.find_all_by_line(&'g');
+        let mut expected_row = HashMap::new();
+        expected_row.insert(1, vec![CoOrd { i: 1, j: 1 }]);
+        expected_row.insert(2, vec![CoOrd { i: 2, j: 3 }]);
+        let mut expected_column = HashMap::new();
+        expected_column.insert(1, vec![CoOrd { i: 1, j: 1 }]);
+        expected_column.insert(3, vec![CoOrd { i: 2, j: 3 }]);
+        assert_eq!((by_row, by_column), (expected_row, expected_column));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+// Compiled once, since integers is called for every line of some inputs
+static NUMBER_RX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"-?\d+").unwrap());
+
+// One line of puzzle input and its 1-based line number, so that anything parsed
+// out of it can say exactly where it went wrong
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    // `part` has to be a slice of `text`; its column is worked out from that
+    pub fn error(&self, part: &str, message: impl Display) -> Error {
+        Error::Syntax {
+            line: self.number,
+            column: self.column_of(part),
+            message: message.to_string(),
+        }
+    }
+
+    fn column_of(&self, part: &str) -> usize {
+        let offset = (part.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+        offset.min(self.text.len()) + 1
+    }
+
+    pub fn parse<T>(&self, field: &'a str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let field = field.trim();
+        field
+            .parse()
+            .map_err(|e| self.error(field, format!("{field:?}: {e}")))
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+// Groups of lines separated by one or more blank lines, numbered as in the input
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = vec![Vec::new()];
+    for line in lines(input) {
+        if !line.is_blank() {
+            sections.last_mut().unwrap().push(line);
+        } else if !sections.last().unwrap().is_empty() {
+            sections.push(Vec::new());
+        }
+    }
+    sections.retain(|section| !section.is_empty());
+    sections
+}
+
+// Whitespace separated fields
+pub fn words<'a, T>(line: Line<'a>) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    line.text
+        .split_whitespace()
+        .map(|word| line.parse(word))
+        .collect()
+}
+
+// Fields between `delimiter`s, e.g. "75,47,61"
+pub fn delimited<'a, T>(line: Line<'a>, delimiter: char) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    line.text
+        .split(delimiter)
+        .map(|field| line.parse(field))
+        .collect()
+}
+
+// Every integer in the line, whatever is between them
+pub fn integers<'a, T>(line: Line<'a>) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    NUMBER_RX
+        .find_iter(line.text)
+        .map(|m| line.parse(m.as_str()))
+        .collect()
+}
+
+// Exactly two fields either side of `delimiter`, e.g. "47|53"
+pub fn pair<'a, T>(line: Line<'a>, delimiter: char) -> Result<(T, T)>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match line.text.split_once(delimiter) {
+        Some((left, right)) if !right.contains(delimiter) => {
+            Ok((line.parse(left)?, line.parse(right)?))
+        }
+        _ => Err(line.error(
+            line.text,
+            format!("expected two values separated by {delimiter:?}"),
+        )),
+    }
+}
+
+// "key: values", with the values whitespace separated, e.g. "3267: 81 40 27"
+pub fn key_values<'a, K, V>(line: Line<'a>, delimiter: char) -> Result<(K, Vec<V>)>
+where
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    let (key, values) = line
+        .text
+        .split_once(delimiter)
+        .ok_or_else(|| line.error(line.text, format!("expected {delimiter:?} after the key")))?;
+    let values = values
+        .split_whitespace()
+        .map(|value| line.parse(value))
+        .collect::<Result<_>>()?;
+    Ok((line.parse(key)?, values))
+}
+
+// Each character as a single digit, e.g. "2333133121414131402"
+pub fn digits(line: Line<'_>) -> Result<Vec<u32>> {
+    line.text
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10).ok_or_else(|| {
+                line.error(&line.text[i..], format!("expected a digit, found {c:?}"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(input: &str) -> Line<'_> {
+        lines(input).next().unwrap()
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "47|53\n97|13\n\n\n75,47,61\n\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0][1].text, "97|13");
+        assert_eq!(
+            sections[1],
+            vec![Line {
+                number: 5,
+                text: "75,47,61"
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(words::<u32>(first("3   4")).unwrap(), vec![3, 4]);
+        assert_eq!(
+            delimited::<u32>(first("75,47,61"), ',').unwrap(),
+            vec![75, 47, 61]
+        );
+        assert_eq!(
+            integers::<i64>(first("p=0,4 v=-3,3")).unwrap(),
+            vec![0, 4, -3, 3]
+        );
+        assert_eq!(pair::<u32>(first("47|53"), '|').unwrap(), (47, 53));
+        assert_eq!(
+            key_values::<u64, u64>(first("3267: 81 40 27"), ':').unwrap(),
+            (3267, vec![81, 40, 27])
+        );
+        assert_eq!(digits(first("2333")).unwrap(), vec![2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_errors_point_at_the_problem() {
+        let input = "1 2 3\n7 6 x 2";
+        let line = lines(input).nth(1).unwrap();
+        let error = words::<i32>(line).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Syntax {
+                line: 2,
+                column: 5,
+                ..
+            }
+        ));
+
+        assert!(matches!(
+            digits(first("23a3")),
+            Err(Error::Syntax { column: 3, .. })
+        ));
+        assert!(matches!(
+            pair::<u32>(first("1|2|3"), '|'),
+            Err(Error::Syntax { column: 1, .. })
+        ));
+        assert!(matches!(
+            key_values::<u64, u64>(first("190: 10 1x"), ':'),
+            Err(Error::Syntax { column: 9, .. })
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+// A set of usize positions stored as sorted, non-overlapping, non-touching
+// half-open ranges, so [0..3, 3..5] is always kept as [0..5]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeSet {
+    ranges: Vec<Range<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        // Everything touching the new range gets folded into it
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        // Only the two ends can survive, trimmed back to the removed range
+        let before = self.ranges[first].start..range.start;
+        let after = range.end..self.ranges[last - 1].end;
+        let kept: Vec<Range<usize>> = [before, after]
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect();
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= position);
+        self.ranges.get(i).is_some_and(|r| r.contains(&position))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Range<usize>> {
+        self.ranges.iter()
+    }
+
+    // How many positions are in the set
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut result = RangeSet::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            result.insert(x.start.max(y.start)..x.end.min(y.end));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        result
+    }
+
+    // Everything in `bounds` that isn't in the set
+    pub fn complement(&self, bounds: Range<usize>) -> RangeSet {
+        RangeSet {
+            ranges: self.gaps(bounds).collect(),
+        }
+    }
+
+    // The stretches of `bounds` not covered by the set, in order
+    pub fn gaps(&self, bounds: Range<usize>) -> impl Iterator<Item = Range<usize>> + '_ {
+        let mut cursor = bounds.start;
+        self.ranges
+            .iter()
+            .map(|r| r.start..r.end)
+            .chain(std::iter::once(bounds.end..bounds.end))
+            .filter_map(move |r| {
+                let gap = cursor..r.start.min(bounds.end);
+                cursor = cursor.max(r.end);
+                (!gap.is_empty()).then_some(gap)
+            })
+    }
+
+    // The leftmost gap in `bounds` with room for `length` positions
+    pub fn first_gap(&self, bounds: Range<usize>, length: usize) -> Option<Range<usize>> {
+        self.gaps(bounds).find(|gap| gap.len() >= length)
+    }
+}
+
+impl FromIterator<Range<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(set: &RangeSet) -> Vec<Range<usize>> {
+        set.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set: RangeSet = [5..7, 0..2, 10..12].into_iter().collect();
+        assert_eq!(ranges(&set), vec![0..2, 5..7, 10..12]);
+        set.insert(2..5);
+        assert_eq!(ranges(&set), vec![0..7, 10..12]);
+        set.insert(6..11);
+        assert_eq!(ranges(&set), vec![0..12]);
+        set.insert(3..3);
+        assert_eq!(set.len(), 12);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set: RangeSet = [0..10, 12..15].into_iter().collect();
+        set.remove(3..5);
+        assert_eq!(ranges(&set), vec![0..3, 5..10, 12..15]);
+        set.remove(8..13);
+        assert_eq!(ranges(&set), vec![0..3, 5..8, 13..15]);
+        set.remove(0..20);
+        assert!(set.is_empty());
+        assert!(!set.contains(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet = [0..4, 6..10].into_iter().collect();
+        let b: RangeSet = [2..7, 9..12].into_iter().collect();
+        assert_eq!(ranges(&a.union(&b)), vec![0..12]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![2..4, 6..7, 9..10]);
+        assert_eq!(ranges(&a.complement(0..12)), vec![4..6, 10..12]);
+        assert_eq!(ranges(&a.complement(2..8)), vec![4..6]);
+        assert!(a.contains(3) && !a.contains(4) && a.contains(9) && !a.contains(10));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set: RangeSet = [2..4, 5..6, 9..10].into_iter().collect();
+        assert_eq!(
+            set.gaps(0..12).collect::<Vec<_>>(),
+            vec![0..2, 4..5, 6..9, 10..12]
+        );
+        assert_eq!(set.first_gap(0..12, 3), Some(6..9));
+        assert_eq!(set.first_gap(0..8, 3), None);
+        assert_eq!(RangeSet::new().first_gap(0..5, 5), Some(0..5));
+    }
+}
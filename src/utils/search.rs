@@ -0,0 +1,292 @@
+use super::{CoOrd, Direction};
+use ndarray::ArrayView2;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// Everything reachable from the start, how far away it is, and every node that
+// leads to it along a shortest path. The start has no predecessors.
+#[derive(Clone, Debug)]
+pub struct Searched<N> {
+    pub start: N,
+    pub distances: HashMap<N, u64>,
+    pub predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N> Searched<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn new(start: N) -> Self {
+        let mut distances = HashMap::new();
+        distances.insert(start.clone(), 0);
+        Searched {
+            start,
+            distances,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    // Records `from` as a way into `to`, returning true if that's a new best.
+    // Nothing leads back into the start, even for free.
+    fn relax(&mut self, from: &N, to: N, cost: u64) -> bool {
+        if to == self.start {
+            return false;
+        }
+        match self.distances.get(&to) {
+            Some(&known) if cost > known => false,
+            Some(&known) if cost == known => {
+                self.predecessors.entry(to).or_default().push(from.clone());
+                false
+            }
+            _ => {
+                self.distances.insert(to.clone(), cost);
+                self.predecessors.insert(to, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    // One shortest path, start first; None if the goal was never reached
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        self.distances.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while *path.last()? != self.start {
+            path.push(self.predecessors.get(path.last()?)?.first()?.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Every shortest path, start first. There can be a lot of these.
+    pub fn all_paths_to(&self, goal: &N) -> Vec<Vec<N>> {
+        if !self.distances.contains_key(goal) {
+            return Vec::new();
+        }
+        let mut paths = Vec::new();
+        self.extend_back(&mut vec![goal.clone()], &mut paths);
+        paths
+    }
+
+    // Walks back from the last node of `tail` (held goal first) to the start.
+    // Zero-cost steps can make nodes each other's predecessors, so a node
+    // already on the tail is never revisited.
+    fn extend_back(&self, tail: &mut Vec<N>, paths: &mut Vec<Vec<N>>) {
+        let Some(node) = tail.last() else {
+            return;
+        };
+        if *node == self.start {
+            paths.push(tail.iter().rev().cloned().collect());
+            return;
+        }
+        for previous in self.predecessors.get(node).into_iter().flatten() {
+            if !tail.contains(previous) {
+                tail.push(previous.clone());
+                self.extend_back(tail, paths);
+                tail.pop();
+            }
+        }
+    }
+}
+
+// Every step costs one
+pub fn bfs<N, F, I>(start: N, mut neighbours: F) -> Searched<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut searched = Searched::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let next = searched.distances[&node] + 1;
+        for neighbour in neighbours(&node) {
+            if searched.relax(&node, neighbour.clone(), next) {
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    searched
+}
+
+// Steps come with their own non-negative cost
+pub fn dijkstra<N, F, I>(start: N, mut neighbours: F) -> Searched<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut searched = Searched::new(start.clone());
+    // The heap holds indices into `nodes` so N doesn't have to be Ord
+    let mut nodes = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if cost > searched.distances[&node] {
+            continue;
+        }
+        for (neighbour, step) in neighbours(&node) {
+            if searched.relax(&node, neighbour.clone(), cost + step) {
+                heap.push(Reverse((cost + step, nodes.len())));
+                nodes.push(neighbour);
+            }
+        }
+    }
+    searched
+}
+
+// Dijkstra steered by `heuristic`, which must never overestimate the remaining
+// cost. Stops at the first goal reached and returns its cost and path.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    mut neighbours: F,
+    heuristic: H,
+    is_goal: G,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: Fn(&N) -> u64,
+    G: Fn(&N) -> bool,
+{
+    let mut searched = Searched::new(start.clone());
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut nodes = vec![start];
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if cost > searched.distances[&node] {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((cost, searched.path_to(&node)?));
+        }
+        for (neighbour, step) in neighbours(&node) {
+            if searched.relax(&node, neighbour.clone(), cost + step) {
+                heap.push(Reverse((
+                    cost + step + heuristic(&neighbour),
+                    cost + step,
+                    nodes.len(),
+                )));
+                nodes.push(neighbour);
+            }
+        }
+    }
+    None
+}
+
+// A neighbours closure for bfs: the orthogonal cells that `passable` allows,
+// on a Grid or a bare Array2
+pub fn open_neighbours<'a, T, P>(
+    cells: impl Into<ArrayView2<'a, T>>,
+    passable: P,
+) -> impl Fn(&CoOrd) -> Vec<CoOrd> + 'a
+where
+    T: 'a,
+    P: Fn(&T) -> bool + 'a,
+{
+    let cells = cells.into();
+    move |at: &CoOrd| {
+        Direction::ORTHOGONAL
+            .iter()
+            .filter_map(|d| at.checked_add(d.delta(), cells.dim()))
+            .filter(|next| passable(&cells[(next.i, next.j)]))
+            .collect()
+    }
+}
+
+// An admissible A* heuristic for orthogonal moves costing at least one each
+pub fn manhattan(a: &CoOrd, b: &CoOrd) -> u64 {
+    (*a - *b).manhattan()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Grid;
+    use ndarray::array;
+
+    const MAZE: &str = r#"S..#
+.#..
+...#
+#..E"#;
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse(MAZE).unwrap();
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let searched = bfs(start, open_neighbours(&grid, |&c| c != '#'));
+
+        assert_eq!(searched.distance(&end), Some(6));
+        let path = searched.path_to(&end).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert!(path.windows(2).all(|w| (w[1] - w[0]).manhattan() == 1));
+        // Two ways in from the left side, one along the top
+        assert_eq!(searched.all_paths_to(&end).len(), 3);
+        assert_eq!(searched.distance(&CoOrd { i: 0, j: 3 }), None);
+        assert!(searched.all_paths_to(&CoOrd { i: 0, j: 3 }).is_empty());
+    }
+
+    #[test]
+    fn test_bfs_on_array() {
+        let cells = array![[0, 0, 1], [1, 0, 1], [1, 0, 0]];
+        let searched = bfs(CoOrd { i: 0, j: 0 }, open_neighbours(&cells, |&c| c == 0));
+        assert_eq!(searched.distance(&CoOrd { i: 2, j: 2 }), Some(4));
+        assert_eq!(searched.distance(&CoOrd { i: 0, j: 2 }), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // a -> b -> d costs 1 + 4, a -> c -> d costs 2 + 3, a -> d costs 7
+        let edges: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('a', vec![('b', 1), ('c', 2), ('d', 7)]),
+            ('b', vec![('d', 4)]),
+            ('c', vec![('d', 3)]),
+            ('d', vec![]),
+        ]);
+        let searched = dijkstra('a', |node| edges[node].clone());
+
+        assert_eq!(searched.distance(&'d'), Some(5));
+        let mut paths = searched.all_paths_to(&'d');
+        paths.sort();
+        assert_eq!(paths, vec![vec!['a', 'b', 'd'], vec!['a', 'c', 'd']]);
+        assert_eq!(searched.path_to(&'a'), Some(vec!['a']));
+    }
+
+    #[test]
+    fn test_zero_cost_cycle() {
+        let edges: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('a', vec![('b', 0)]),
+            ('b', vec![('a', 0), ('c', 0)]),
+            ('c', vec![('b', 0), ('d', 1)]),
+            ('d', vec![]),
+        ]);
+        let searched = dijkstra('a', |node| edges[node].clone());
+
+        assert!(!searched.predecessors.contains_key(&'a'));
+        assert_eq!(searched.path_to(&'b'), Some(vec!['a', 'b']));
+        assert_eq!(searched.path_to(&'d'), Some(vec!['a', 'b', 'c', 'd']));
+        assert_eq!(searched.all_paths_to(&'d'), vec![vec!['a', 'b', 'c', 'd']]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = Grid::parse(MAZE).unwrap();
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let open = open_neighbours(&grid, |&c| c != '#');
+        let steps = |at: &CoOrd| open(at).into_iter().map(|next| (next, 1));
+
+        let (cost, path) = astar(start, steps, |at| manhattan(at, &end), |at| *at == end).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert!(astar(start, steps, |_| 0, |at| grid[*at] == 'X').is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use super::CoOrd;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// A signed step between two co-ords, in the same (i, j) = (row, column) order
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Vec2 {
+    pub i: i64,
+    pub j: i64,
+}
+
+impl Vec2 {
+    pub const fn new(i: i64, j: i64) -> Self {
+        Vec2 { i, j }
+    }
+
+    // Rows plus columns, i.e. how many orthogonal steps it covers
+    pub fn manhattan(&self) -> u64 {
+        self.i.unsigned_abs() + self.j.unsigned_abs()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.i + other.i, self.j + other.j)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.i - other.i, self.j - other.j)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.i, -self.j)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, times: i64) -> Vec2 {
+        Vec2::new(self.i * times, self.j * times)
+    }
+}
+
+// The step that takes you from `other` to `self`
+impl Sub for CoOrd {
+    type Output = Vec2;
+
+    fn sub(self, other: CoOrd) -> Vec2 {
+        Vec2::new(
+            self.i as i64 - other.i as i64,
+            self.j as i64 - other.j as i64,
+        )
+    }
+}
+
+// Panics if the step goes off the top or left edge; see checked_add
+impl Add<Vec2> for CoOrd {
+    type Output = CoOrd;
+
+    fn add(self, step: Vec2) -> CoOrd {
+        let offset = |at: usize, by: i64| {
+            usize::try_from(at as i64 + by).expect("co-ord moved off the top or left edge")
+        };
+        CoOrd {
+            i: offset(self.i, step.i),
+            j: offset(self.j, step.j),
+        }
+    }
+}
+
+impl Sub<Vec2> for CoOrd {
+    type Output = CoOrd;
+
+    fn sub(self, step: Vec2) -> CoOrd {
+        self + -step
+    }
+}
+
+impl CoOrd {
+    // None if the step would leave a grid of `size` = (height, width)
+    pub fn checked_add(self, step: Vec2, size: (usize, usize)) -> Option<CoOrd> {
+        let i = usize::try_from(self.i as i64 + step.i).ok()?;
+        let j = usize::try_from(self.j as i64 + step.j).ok()?;
+        (i < size.0 && j < size.1).then_some(CoOrd { i, j })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = CoOrd { i: 2, j: 5 };
+        let b = CoOrd { i: 4, j: 1 };
+        assert_eq!(b - a, Vec2::new(2, -4));
+        assert_eq!(a - b, -(b - a));
+        assert_eq!((b - a) * 3, Vec2::new(6, -12));
+        assert_eq!(Vec2::new(1, 1) + Vec2::new(-2, 3), Vec2::new(-1, 4));
+        assert_eq!(Vec2::new(1, 1) - Vec2::new(-2, 3), Vec2::new(3, -2));
+        assert_eq!((b - a).manhattan(), 6);
+        assert_eq!(a + (b - a), b);
+        assert_eq!(b - (b - a), a);
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let a = CoOrd { i: 2, j: 5 };
+        assert_eq!(
+            a.checked_add(Vec2::new(1, -5), (4, 6)),
+            Some(CoOrd { i: 3, j: 0 })
+        );
+        assert_eq!(a.checked_add(Vec2::new(0, -6), (4, 6)), None);
+        assert_eq!(a.checked_add(Vec2::new(2, 0), (4, 6)), None);
+        assert_eq!(a.checked_add(Vec2::new(0, 1), (4, 6)), None);
+    }
+}
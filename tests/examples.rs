@@ -0,0 +1,3 @@
+use adventofcode2024::{example_tests, registered_days};
+
+registered_days!(example_tests);